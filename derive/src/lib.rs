@@ -1,14 +1,69 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+
 use heck::{CamelCase, SnakeCase};
 use proc_macro::TokenStream as TStream;
 use proc_macro2::TokenStream;
 use proc_macro_error::proc_macro_error;
 use quote::{format_ident, quote, ToTokens};
-use syn::{Ident, LitStr, Token, Visibility, parse::Parse, parse_macro_input};
+use serde::Deserialize;
+use syn::{
+    braced, bracketed, parenthesized, parse::Parse, parse_macro_input, punctuated::Punctuated,
+    Ident, LitInt, LitStr, Token, Visibility,
+};
+
+/// Namespace used to derive the `TYPE_UUID`s of all generated types
+const UUID_NAMESPACE: uuid::Uuid = uuid::Uuid::from_bytes([
+    0xb6, 0x54, 0xb3, 0x2d, 0x24, 0xdd, 0x4e, 0x27, 0x99, 0xc5, 0x20, 0x9d, 0x18, 0xb5, 0xe4, 0xba,
+]);
 
 struct DataConfigDeclaration {
     vis: Visibility,
     name: Ident,
     path: LitStr,
+    format: Option<Ident>,
+    uuids: Vec<KeyPathOption<LitStr>>,
+    types: Vec<KeyPathOption<Ident>>,
+    constraints: Vec<KeyPathOption<Constraints>>,
+    lenient: bool,
+    unknown_keys: UnknownKeys,
+}
+
+/// What happens to keys of a file read at runtime which are not part of the generated types
+#[derive(Clone, Copy, PartialEq)]
+enum UnknownKeys {
+    Ignore,
+    Warn,
+    Deny,
+}
+
+/// A `"key.path" => value` entry of one of the options of the invocation
+struct KeyPathOption<T> {
+    path: LitStr,
+    value: T,
+}
+
+impl<T: Parse> Parse for KeyPathOption<T> {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let path: LitStr = input.parse()?;
+        input.parse::<Token!(=>)>()?;
+        let value: T = input.parse()?;
+
+        Ok(KeyPathOption { path, value })
+    }
+}
+
+fn parse_key_path_options<T: Parse>(
+    input: syn::parse::ParseStream,
+) -> syn::Result<Vec<KeyPathOption<T>>> {
+    let content;
+    braced!(content in input);
+
+    Ok(
+        Punctuated::<KeyPathOption<T>, Token!(,)>::parse_terminated(&content)?
+            .into_iter()
+            .collect(),
+    )
 }
 
 impl Parse for DataConfigDeclaration {
@@ -18,34 +73,880 @@ impl Parse for DataConfigDeclaration {
         input.parse::<Token!(,)>()?;
         let path: LitStr = input.parse()?;
 
-        Ok(DataConfigDeclaration { vis, name, path })
+        let mut format = None;
+        let mut uuids = vec![];
+        let mut types = vec![];
+        let mut constraints = vec![];
+        let mut lenient = false;
+        let mut unknown_keys = UnknownKeys::Ignore;
+
+        while !input.is_empty() {
+            input.parse::<Token!(,)>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let option: Ident = input.parse()?;
+            match option.to_string().as_str() {
+                "format" => {
+                    input.parse::<Token!(=)>()?;
+                    format = Some(input.parse()?);
+                }
+                "uuids" => uuids.extend(parse_key_path_options(input)?),
+                "types" => types.extend(parse_key_path_options(input)?),
+                "constraints" => constraints.extend(parse_key_path_options(input)?),
+                "lenient" => lenient = true,
+                "strict" if input.peek(Token!(=)) => {
+                    input.parse::<Token!(=)>()?;
+                    let mode: Ident = input.parse()?;
+                    unknown_keys = match mode.to_string().as_str() {
+                        "deny" => UnknownKeys::Deny,
+                        "warn" => UnknownKeys::Warn,
+                        _ => {
+                            return Err(syn::Error::new(
+                                mode.span(),
+                                "Unknown strictness, expected `deny` or `warn`",
+                            ));
+                        }
+                    };
+                }
+                "strict" => unknown_keys = UnknownKeys::Deny,
+                other => {
+                    return Err(syn::Error::new(
+                        option.span(),
+                        format!(
+                            "Unknown option `{}`, expected `format`, `uuids`, `types`, `constraints`, `lenient` or `strict`",
+                            other
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(DataConfigDeclaration {
+            vis,
+            name,
+            path,
+            format,
+            uuids,
+            types,
+            constraints,
+            lenient,
+            unknown_keys,
+        })
+    }
+}
+
+/// A bound of a number, which may be negative
+#[derive(Clone, Copy)]
+enum Number {
+    Integer(i128),
+    Float(f64),
+}
+
+impl Parse for Number {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let negative = input.parse::<Option<Token!(-)>>()?.is_some();
+        let number = if input.peek(LitInt) {
+            Number::Integer(input.parse::<LitInt>()?.base10_parse()?)
+        } else {
+            Number::Float(input.parse::<syn::LitFloat>()?.base10_parse()?)
+        };
+
+        Ok(match number {
+            Number::Integer(integer) if negative => Number::Integer(-integer),
+            Number::Float(float) if negative => Number::Float(-float),
+            number => number,
+        })
+    }
+}
+
+impl Number {
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Integer(integer) => integer as f64,
+            Number::Float(float) => float,
+        }
+    }
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Number::Integer(integer) => write!(f, "{}", integer),
+            Number::Float(float) => write!(f, "{}", float),
+        }
+    }
+}
+
+/// The constraints a value has to satisfy, e.g. `(min = 1, max = 500)`
+///
+/// Each constraint keeps the name it was given with, to point errors at it.
+#[derive(Default)]
+struct Constraints {
+    min: Option<(Ident, Number)>,
+    max: Option<(Ident, Number)>,
+    one_of: Option<(Ident, Vec<String>)>,
+    regex: Option<(Ident, String)>,
+    min_len: Option<(Ident, usize)>,
+    max_len: Option<(Ident, usize)>,
+}
+
+impl Parse for Constraints {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+
+        let mut constraints = Constraints::default();
+        while !content.is_empty() {
+            let name: Ident = content.parse()?;
+            content.parse::<Token!(=)>()?;
+
+            match name.to_string().as_str() {
+                "min" => constraints.min = Some((name, content.parse()?)),
+                "max" => constraints.max = Some((name, content.parse()?)),
+                "one_of" => {
+                    let values;
+                    bracketed!(values in content);
+                    let values = Punctuated::<LitStr, Token!(,)>::parse_terminated(&values)?
+                        .iter()
+                        .map(LitStr::value)
+                        .collect();
+                    constraints.one_of = Some((name, values));
+                }
+                "regex" => constraints.regex = Some((name, content.parse::<LitStr>()?.value())),
+                "min_len" => {
+                    constraints.min_len = Some((name, content.parse::<LitInt>()?.base10_parse()?))
+                }
+                "max_len" => {
+                    constraints.max_len = Some((name, content.parse::<LitInt>()?.base10_parse()?))
+                }
+                other => {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!(
+                            "Unknown constraint `{}`, expected `min`, `max`, `one_of`, `regex`, `min_len` or `max_len`",
+                            other
+                        ),
+                    ));
+                }
+            }
+
+            if !content.is_empty() {
+                content.parse::<Token!(,)>()?;
+            }
+        }
+
+        Ok(constraints)
+    }
+}
+
+/// The options given for specific key paths, keeping track of which of them were used
+struct KeyPathOptions<T> {
+    options: BTreeMap<String, (LitStr, T)>,
+    used: RefCell<BTreeSet<String>>,
+}
+
+impl<T> KeyPathOptions<T> {
+    fn new(options: Vec<(LitStr, T)>) -> Self {
+        let mut by_path = BTreeMap::new();
+        for (path, value) in options {
+            if by_path.contains_key(&path.value()) {
+                proc_macro_error::abort!(
+                    path,
+                    "The key `{}` is given more than once",
+                    path.value()
+                );
+            }
+            by_path.insert(path.value(), (path, value));
+        }
+
+        KeyPathOptions {
+            options: by_path,
+            used: RefCell::new(BTreeSet::new()),
+        }
+    }
+
+    fn get(&self, key_path: &str) -> Option<&T> {
+        let (_, value) = self.options.get(key_path)?;
+        self.used.borrow_mut().insert(key_path.to_owned());
+
+        Some(value)
+    }
+
+    /// Aborts with the given message if any of the options has not been used
+    fn check_unused(&self, message: &str, help: &str) {
+        let used = self.used.borrow();
+        for (key_path, (path, _)) in &self.options {
+            if !used.contains(key_path) {
+                proc_macro_error::abort!(path, "{} `{}`", message, key_path; help = help);
+            }
+        }
+    }
+}
+
+const INTEGER_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize",
+];
+
+fn integer_range(integer: &str) -> (i128, i128) {
+    match integer {
+        "i8" => (i8::MIN.into(), i8::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        "i64" => (i64::MIN.into(), i64::MAX.into()),
+        "isize" => (isize::MIN as i128, isize::MAX as i128),
+        "u8" => (u8::MIN.into(), u8::MAX.into()),
+        "u16" => (u16::MIN.into(), u16::MAX.into()),
+        "u32" => (u32::MIN.into(), u32::MAX.into()),
+        "u64" => (u64::MIN.into(), u64::MAX.into()),
+        _ => (usize::MIN as i128, usize::MAX as i128),
+    }
+}
+
+/// The type of a value, or of the values of an array
+enum LeafType {
+    String,
+    Integer(Ident),
+    Float(Ident),
+    Boolean,
+}
+
+impl LeafType {
+    fn from_ident(ident: &Ident) -> Option<Self> {
+        match ident.to_string().as_str() {
+            "String" => Some(LeafType::String),
+            "bool" => Some(LeafType::Boolean),
+            "f32" | "f64" => Some(LeafType::Float(ident.clone())),
+            integer if INTEGER_TYPES.contains(&integer) => Some(LeafType::Integer(ident.clone())),
+            _ => None,
+        }
+    }
+
+    fn type_tokens(&self) -> TokenStream {
+        match self {
+            LeafType::String => quote! { String },
+            LeafType::Integer(ty) | LeafType::Float(ty) => quote! { #ty },
+            LeafType::Boolean => quote! { bool },
+        }
+    }
+}
+
+/// Information shared by all types generated from a single `data_config!` invocation
+struct GenerationContext {
+    /// The prefix from which all type uuids are derived, `crate::module`
+    uuid_prefix: String,
+    /// Explicitly pinned type uuids
+    pinned_uuids: KeyPathOptions<uuid::Uuid>,
+    /// Explicitly chosen types of the values
+    types: KeyPathOptions<Ident>,
+    /// The visibility of the fields and getters of the generated structs
+    field_vis: TokenStream,
+    /// Constraints the values have to satisfy
+    constraints: KeyPathOptions<Constraints>,
+    /// Whether missing keys are filled in with the values found at compile time
+    lenient: bool,
+    /// What happens to unknown keys in files read at runtime
+    unknown_keys: UnknownKeys,
+}
+
+impl GenerationContext {
+    fn new(
+        vis: &Visibility,
+        module: &Ident,
+        uuids: Vec<KeyPathOption<LitStr>>,
+        types: Vec<KeyPathOption<Ident>>,
+        constraints: Vec<KeyPathOption<Constraints>>,
+        lenient: bool,
+        unknown_keys: UnknownKeys,
+    ) -> Self {
+        let crate_name = std::env::var("CARGO_CRATE_NAME")
+            .or_else(|_| std::env::var("CARGO_PKG_NAME"))
+            .unwrap_or_default();
+
+        let uuids = uuids
+            .into_iter()
+            .map(
+                |KeyPathOption { path, value }| match uuid::Uuid::parse_str(&value.value()) {
+                    Ok(uuid) => (path, uuid),
+                    Err(err) => {
+                        proc_macro_error::abort!(value, "Could not parse uuid"; note = err);
+                    }
+                },
+            )
+            .collect();
+
+        let types = types
+            .into_iter()
+            .map(|KeyPathOption { path, value }| (path, value))
+            .collect();
+
+        // Restricted visibilities are relative to the invocation, which does not
+        // hold for nested modules, so those are widened to the crate.
+        let field_vis = match vis {
+            Visibility::Public(_) => quote! { pub },
+            _ => quote! { pub(crate) },
+        };
+
+        GenerationContext {
+            uuid_prefix: format!("{}::{}", crate_name, module),
+            pinned_uuids: KeyPathOptions::new(uuids),
+            types: KeyPathOptions::new(types),
+            constraints: KeyPathOptions::new(
+                constraints
+                    .into_iter()
+                    .map(|KeyPathOption { path, value }| (path, value))
+                    .collect(),
+            ),
+            field_vis,
+            lenient,
+            unknown_keys,
+        }
+    }
+
+    /// Returns the attributes of the generated structs with named fields
+    fn struct_attributes(&self) -> TokenStream {
+        let mut attributes = TokenStream::new();
+        if self.lenient {
+            attributes.extend(quote! { #[serde(default)] });
+        }
+        if self.unknown_keys == UnknownKeys::Deny {
+            attributes.extend(quote! { #[serde(deny_unknown_fields)] });
+        }
+
+        attributes
+    }
+
+    /// Returns how the loader treats unknown keys
+    fn unknown_keys(&self) -> TokenStream {
+        match self.unknown_keys {
+            UnknownKeys::Ignore => quote! { ::bevy_spicy_data::UnknownKeys::Ignore },
+            UnknownKeys::Warn => quote! { ::bevy_spicy_data::UnknownKeys::Warn },
+            UnknownKeys::Deny => quote! { ::bevy_spicy_data::UnknownKeys::Deny },
+        }
+    }
+
+    /// Returns the `Default` implementation of a generated table in lenient mode,
+    /// which uses the defaults of its fields
+    fn table_default_impl(
+        &self,
+        ty_name: &Ident,
+        toml_types: &[TomlType],
+        optional: &BTreeSet<Vec<String>>,
+    ) -> TokenStream {
+        if !self.lenient {
+            return TokenStream::new();
+        }
+
+        // Reading the table itself would recurse, as serde asks for the default of the whole table first
+        let fields = toml_types.iter().map(|ty| {
+            let field_name = format_ident!("{}", ty.name.to_snake_case());
+            if optional.contains(&vec![ty.name.clone()]) {
+                quote! { #field_name: None }
+            } else {
+                quote! { #field_name: ::std::default::Default::default() }
+            }
+        });
+
+        quote! {
+            impl ::std::default::Default for #ty_name {
+                fn default() -> Self {
+                    Self {
+                        #(#fields),*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the `Default` implementation of any other generated type in lenient mode,
+    /// which reads the value found at compile time
+    fn default_impl(
+        &self,
+        key_path: &[String],
+        ty_name: &Ident,
+        value: &toml::Value,
+    ) -> TokenStream {
+        if !self.lenient {
+            return TokenStream::new();
+        }
+
+        let key_path = key_path.join(".");
+        let value = value_tokens(value);
+
+        quote! {
+            impl ::std::default::Default for #ty_name {
+                fn default() -> Self {
+                    match <Self as ::bevy_spicy_data::private::serde::Deserialize>::deserialize(#value) {
+                        Ok(value) => value,
+                        Err(err) => panic!("Could not read the value of `{}` found at compile time: {}", #key_path, err),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the `TYPE_UUID` of the type generated for the given key path
+    ///
+    /// Unless pinned, it is derived from the crate, the module and the key path,
+    /// so that it stays the same across builds.
+    fn type_uuid(&self, key_path: &[String]) -> TokenStream {
+        let key_path = key_path.join(".");
+
+        let uuid = if let Some(uuid) = self.pinned_uuids.get(&key_path) {
+            *uuid
+        } else {
+            uuid::Uuid::new_v5(
+                &UUID_NAMESPACE,
+                format!("{}::{}", self.uuid_prefix, key_path).as_bytes(),
+            )
+        };
+
+        let bytes = uuid.as_bytes().to_vec();
+        quote! { ::bevy_spicy_data::private::Uuid::from_bytes([#(#bytes),*]) }
+    }
+
+    /// Returns the type used for the values of the given key path
+    ///
+    /// Unless chosen otherwise, it is inferred from the values found in the file.
+    /// Aborts if any of the values do not fit into the chosen type.
+    fn leaf_type(&self, key_path: &[String], values: &[toml::Value]) -> LeafType {
+        let key_path = key_path.join(".");
+
+        let chosen = match self.types.get(&key_path) {
+            Some(chosen) => chosen,
+            None => {
+                // Entries of arrays of tables may mix integers and floats
+                let sample = match values
+                    .iter()
+                    .find(|value| value.is_float())
+                    .or_else(|| values.first())
+                {
+                    Some(sample) => sample,
+                    None => {
+                        proc_macro_error::abort_call_site!(
+                            "Could not infer the element type of the empty array `{}`", key_path;
+                            help = "Add at least one value to the array, or choose its type in `types`"
+                        );
+                    }
+                };
+                return match sample {
+                    toml::Value::String(_) => LeafType::String,
+                    toml::Value::Integer(_) => LeafType::Integer(format_ident!("i64")),
+                    toml::Value::Float(_) => LeafType::Float(format_ident!("f64")),
+                    toml::Value::Boolean(_) => LeafType::Boolean,
+                    other => {
+                        proc_macro_error::abort_call_site!(
+                            "The array `{}` contains values of type {}, which are not supported", key_path, other.type_str();
+                            note = "Only arrays of strings, integers, floats and booleans are supported"
+                        );
+                    }
+                };
+            }
+        };
+
+        let leaf = match LeafType::from_ident(chosen) {
+            Some(leaf) => leaf,
+            None => {
+                proc_macro_error::abort!(chosen, "Unsupported type `{}`", chosen;
+                    note = "Supported types are: String, bool, f32, f64, {}", INTEGER_TYPES.join(", "));
+            }
+        };
+
+        for value in values {
+            match (&leaf, value) {
+                (LeafType::String, toml::Value::String(_))
+                | (LeafType::Boolean, toml::Value::Boolean(_))
+                | (LeafType::Float(_), toml::Value::Float(_))
+                | (LeafType::Float(_), toml::Value::Integer(_)) => (),
+                (LeafType::Integer(integer), toml::Value::Integer(value)) => {
+                    let (min, max) = integer_range(&integer.to_string());
+                    if i128::from(*value) < min || i128::from(*value) > max {
+                        proc_macro_error::abort!(chosen, "The value {} of `{}` does not fit into {}", value, key_path, integer;
+                            note = "{} can hold values from {} to {}", integer, min, max);
+                    }
+                }
+                (_, value) => {
+                    proc_macro_error::abort!(
+                        chosen,
+                        "`{}` is a {} in the file, which can not be read as {}",
+                        key_path,
+                        value.type_str(),
+                        chosen
+                    );
+                }
+            }
+        }
+
+        leaf
+    }
+
+    /// Aborts if a type was chosen for the given key path, which does not hold a value
+    fn reject_type(&self, key_path: &[String], kind: &str) {
+        let key_path = key_path.join(".");
+        if let Some(chosen) = self.types.get(&key_path) {
+            proc_macro_error::abort!(chosen, "`{}` is {}, its type can not be chosen", key_path, kind;
+                help = "Choose the types of the values inside of it instead");
+        }
+    }
+
+    /// Returns the body of `Config::validate` for the given key path, if any constraints were given for it
+    ///
+    /// Aborts if the constraints do not apply to the type of the value,
+    /// or if any of the values found at compile time do not satisfy them.
+    fn validation(
+        &self,
+        key_path: &[String],
+        leaf: &LeafType,
+        values: &[toml::Value],
+        is_array: bool,
+    ) -> Option<TokenStream> {
+        let key_path = key_path.join(".");
+        let constraints = self.constraints.get(&key_path)?;
+
+        let mut checks = vec![];
+        let mut len_checks = vec![];
+
+        for (bound, is_min) in [(&constraints.min, true), (&constraints.max, false)].iter() {
+            let (name, bound) = match bound {
+                Some(bound) => bound,
+                None => continue,
+            };
+            let (description, op) = if *is_min {
+                ("below the minimum", quote! { < })
+            } else {
+                ("above the maximum", quote! { > })
+            };
+            let literal = match (leaf, bound) {
+                (LeafType::Integer(integer), Number::Integer(value)) => {
+                    let (min, max) = integer_range(&integer.to_string());
+                    if *value < min || *value > max {
+                        proc_macro_error::abort!(
+                            name,
+                            "The bound {} does not fit into {}, the type of `{}`",
+                            value,
+                            integer,
+                            key_path
+                        );
+                    }
+                    proc_macro2::Literal::i128_unsuffixed(*value)
+                }
+                (LeafType::Integer(_), Number::Float(_)) => {
+                    proc_macro_error::abort!(
+                        name,
+                        "`{}` holds integers, so `{}` needs to be an integer",
+                        key_path,
+                        name
+                    );
+                }
+                (LeafType::Float(_), bound) => proc_macro2::Literal::f64_unsuffixed(bound.as_f64()),
+                _ => {
+                    proc_macro_error::abort!(
+                        name,
+                        "`{}` does not hold numbers, `{}` can only be given for numbers",
+                        key_path,
+                        name
+                    );
+                }
+            };
+
+            let violates = |value: f64| {
+                if *is_min {
+                    value < bound.as_f64()
+                } else {
+                    value > bound.as_f64()
+                }
+            };
+            if let Some(value) = values
+                .iter()
+                .filter_map(|value| {
+                    value
+                        .as_float()
+                        .or_else(|| value.as_integer().map(|integer| integer as f64))
+                })
+                .find(|value| violates(*value))
+            {
+                proc_macro_error::abort!(
+                    name,
+                    "The value {} of `{}` is {} of {}",
+                    value,
+                    key_path,
+                    description,
+                    bound
+                );
+            }
+
+            let bound = bound.to_string();
+            checks.push(quote! {
+                if *value #op #literal {
+                    failures.push(format!("`{}` is {}, {} of {}", key_path, value, #description, #bound));
+                }
+            });
+        }
+
+        if let Some((name, allowed)) = &constraints.one_of {
+            if !matches!(leaf, LeafType::String) {
+                proc_macro_error::abort!(
+                    name,
+                    "`{}` does not hold strings, `one_of` can only be given for strings",
+                    key_path
+                );
+            }
+            if let Some(value) = values
+                .iter()
+                .filter_map(toml::Value::as_str)
+                .find(|value| !allowed.iter().any(|allowed| allowed == value))
+            {
+                proc_macro_error::abort!(
+                    name,
+                    "The value {:?} of `{}` is not one of the allowed values",
+                    value,
+                    key_path
+                );
+            }
+
+            let listed = allowed
+                .iter()
+                .map(|value| format!("{:?}", value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            checks.push(quote! {
+                if ![#(#allowed),*].contains(&value.as_str()) {
+                    failures.push(format!("`{}` is {:?}, which is not one of {}", key_path, value, #listed));
+                }
+            });
+        }
+
+        if let Some((name, pattern)) = &constraints.regex {
+            if !matches!(leaf, LeafType::String) {
+                proc_macro_error::abort!(
+                    name,
+                    "`{}` does not hold strings, `regex` can only be given for strings",
+                    key_path
+                );
+            }
+            let regex = match regex::Regex::new(pattern) {
+                Ok(regex) => regex,
+                Err(err) => {
+                    proc_macro_error::abort!(name, "Could not parse the regex of `{}`", key_path; note = err);
+                }
+            };
+            if let Some(value) = values
+                .iter()
+                .filter_map(toml::Value::as_str)
+                .find(|value| !regex.is_match(value))
+            {
+                proc_macro_error::abort!(
+                    name,
+                    "The value {:?} of `{}` does not match `{}`",
+                    value,
+                    key_path,
+                    pattern
+                );
+            }
+
+            checks.push(quote! {
+                {
+                    // Compiled on first use, the pattern has been checked above
+                    static REGEX: ::bevy_spicy_data::private::Lazy<::bevy_spicy_data::private::Regex> =
+                        ::bevy_spicy_data::private::Lazy::new(|| {
+                            ::bevy_spicy_data::private::Regex::new(#pattern).expect("The regex was checked by data_config!")
+                        });
+                    if !REGEX.is_match(value) {
+                        failures.push(format!("`{}` is {:?}, which does not match `{}`", key_path, value, #pattern));
+                    }
+                }
+            });
+        }
+
+        for (bound, is_min) in [(&constraints.min_len, true), (&constraints.max_len, false)].iter()
+        {
+            let (name, bound) = match bound {
+                Some(bound) => bound,
+                None => continue,
+            };
+            let (description, op) = if *is_min {
+                ("below the minimum", quote! { < })
+            } else {
+                ("above the maximum", quote! { > })
+            };
+            let violates = |len: usize| if *is_min { len < *bound } else { len > *bound };
+            let literal = proc_macro2::Literal::usize_unsuffixed(*bound);
+
+            if is_array {
+                // The values of arrays inside of arrays of tables are merged, so their lengths are unknown
+                if !key_path.contains('*') && violates(values.len()) {
+                    proc_macro_error::abort!(
+                        name,
+                        "`{}` has a length of {}, {} of {}",
+                        key_path,
+                        values.len(),
+                        description,
+                        bound
+                    );
+                }
+
+                len_checks.push(quote! {
+                    if self.0.len() #op #literal {
+                        failures.push(format!("`{}` has a length of {}, {} of {}", key_path, self.0.len(), #description, #literal));
+                    }
+                });
+            } else if let LeafType::String = leaf {
+                if let Some(value) = values
+                    .iter()
+                    .filter_map(toml::Value::as_str)
+                    .find(|value| violates(value.chars().count()))
+                {
+                    proc_macro_error::abort!(
+                        name,
+                        "The value {:?} of `{}` has a length of {}, {} of {}",
+                        value,
+                        key_path,
+                        value.chars().count(),
+                        description,
+                        bound
+                    );
+                }
+
+                checks.push(quote! {
+                    if value.chars().count() #op #literal {
+                        failures.push(format!("`{}` has a length of {}, {} of {}", key_path, value.chars().count(), #description, #literal));
+                    }
+                });
+            } else {
+                proc_macro_error::abort!(
+                    name,
+                    "`{}` is neither a string nor an array, `{}` can only be given for those",
+                    key_path,
+                    name
+                );
+            }
+        }
+
+        if checks.is_empty() && len_checks.is_empty() {
+            return None;
+        }
+
+        let checks = if checks.is_empty() {
+            TokenStream::new()
+        } else if is_array {
+            quote! {
+                for (index, value) in self.0.iter().enumerate() {
+                    let key_path = format!("{}.{}", key_path, index);
+                    #(#checks)*
+                }
+            }
+        } else {
+            quote! {
+                let value = &self.0;
+                #(#checks)*
+            }
+        };
+
+        Some(quote! {
+            let mut failures = Vec::new();
+            let key_path = path.join(".");
+            #(#len_checks)*
+            #checks
+            failures
+        })
+    }
+
+    /// Aborts if any of the key path options have not been used
+    fn check_unused(&self) {
+        self.pinned_uuids.check_unused(
+            "There is no key",
+            "Use \"\" to pin the uuid of `Root` and `*` for the entries of an array of tables, e.g. \"waves.*\"",
+        );
+        self.types.check_unused(
+            "There is no key",
+            "Use `*` for the entries of an array of tables, e.g. \"waves.*.count\"",
+        );
+        self.constraints.check_unused(
+            "There is no value",
+            "Constraints can only be given for values, use `*` for the entries of an array of tables, e.g. \"waves.*.count\"",
+        );
     }
 }
 
 #[proc_macro]
 #[proc_macro_error]
 pub fn data_config(input: TStream) -> TStream {
-    let DataConfigDeclaration { vis, name, path } =
-        parse_macro_input!(input as DataConfigDeclaration);
+    let DataConfigDeclaration {
+        vis,
+        name,
+        path,
+        format,
+        uuids,
+        types,
+        constraints,
+        lenient,
+        unknown_keys,
+    } = parse_macro_input!(input as DataConfigDeclaration);
 
-    let toml_file = match std::fs::read(path.value()) {
+    let file_path = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(manifest_dir) => std::path::Path::new(&manifest_dir).join(path.value()),
+        None => std::path::PathBuf::from(path.value()),
+    };
+
+    let toml_file = match std::fs::read(&file_path) {
         Ok(val) => val,
         Err(err) => {
-            proc_macro_error::abort!(path, "Could not read file."; note = err; note = "Make sure the file path is relative to the workspace root");
+            proc_macro_error::abort!(path, "Could not read file."; note = err; note = "Make sure the file path is relative to the directory containing your Cargo.toml");
         }
     };
 
-    let toml_config: toml::Value = match toml::from_slice(&toml_file) {
+    let format = match &format {
+        Some(format) => match format.to_string().as_str() {
+            "toml" => "toml",
+            "json" => "json",
+            "ron" => "ron",
+            _ => {
+                proc_macro_error::abort!(format, "Unknown format `{}`", format; note = "Supported formats are: toml, json, ron");
+            }
+        },
+        None => match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => "json",
+            Some("ron") => "ron",
+            _ => "toml",
+        },
+    };
+
+    let parsed = match format {
+        "json" => serde_json::from_slice(&toml_file).map_err(|err| err.to_string()),
+        // RON only allows deserializing the keys of structs as identifiers
+        "ron" => ron::de::from_bytes::<ron::Value>(&toml_file)
+            .map_err(|err| err.to_string())
+            .and_then(|val| toml::Value::deserialize(val).map_err(|err| err.to_string())),
+        _ => toml::from_slice(&toml_file).map_err(|err| err.to_string()),
+    };
+
+    let toml_config: toml::Value = match parsed {
         Ok(val) => val,
         Err(err) => {
-            proc_macro_error::abort!(path, "Could not read toml"; note = err);
+            proc_macro_error::abort!(path, "Could not read {}", format; note = err);
         }
     };
 
-    let modules = generate_modules(toml_config);
+    let ctx = GenerationContext::new(
+        &vis,
+        &name,
+        uuids,
+        types,
+        constraints,
+        lenient,
+        unknown_keys,
+    );
+    let modules = generate_modules(&ctx, toml_config);
+    ctx.check_unused();
+
+    // Makes sure the module is regenerated whenever the file changes
+    let file_path = LitStr::new(&file_path.to_string_lossy(), path.span());
 
     let expanded = quote! {
         #vis mod #name {
+            const _: &[u8] = include_bytes!(#file_path);
+
             #modules
         }
     };
@@ -56,14 +957,20 @@ pub fn data_config(input: TStream) -> TStream {
 #[derive(Debug)]
 struct TomlTypeDefinition {
     name: Ident,
+    attributes: TokenStream,
     typ: TokenStream,
 }
 
 impl ToTokens for TomlTypeDefinition {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let TomlTypeDefinition { name, typ } = self;
+        let TomlTypeDefinition {
+            name,
+            attributes,
+            typ,
+        } = self;
         tokens.extend(quote! {
             #[derive(::bevy_spicy_data::private::serde::Deserialize, Debug, Clone, PartialEq)]
+            #attributes
             pub struct #name#typ
         })
     }
@@ -74,14 +981,91 @@ struct TomlType {
     name: String,
     definition: TomlTypeDefinition,
     builder: TokenStream,
+    getter: Getter,
+}
+
+/// How the getter of a parent struct returns the value of a field
+#[derive(Debug)]
+enum Getter {
+    /// Returns a copy of the inner value of the given type
+    Copy(TokenStream),
+    /// Returns a reference to the inner value, as the given type
+    Borrow(TokenStream),
+    /// Returns the entries of an array of tables, with the given path of the entry type
+    Entries(TokenStream),
+    /// Returns a reference to the value itself
+    Itself,
 }
 
-fn generate_modules(toml_config: toml::Value) -> TokenStream {
+/// The functions generated by `make_label`, which keys may not be named after
+const LABEL_FUNCTIONS: &[&str] = &["load_from", "label_at"];
+
+/// Generates the getter for a field, `prefix` being the path to the module containing the type of the field
+fn make_getter(
+    ctx: &GenerationContext,
+    ty: &TomlType,
+    prefix: &TokenStream,
+    optional: bool,
+) -> TokenStream {
+    let TomlType {
+        name,
+        definition: TomlTypeDefinition { name: ty_name, .. },
+        getter,
+        ..
+    } = ty;
+    let field_name = format_ident!("{}", name.to_snake_case());
+    let vis = &ctx.field_vis;
+
+    // These are generated next to the getters by `make_label`
+    if LABEL_FUNCTIONS.contains(&field_name.to_string().as_str()) {
+        proc_macro_error::abort_call_site!(
+            "The key `{}` clashes with the function `{}` generated to load sub-assets", name, field_name;
+            note = "The names {} are reserved, rename the key in the file", LABEL_FUNCTIONS.join(", ")
+        );
+    }
+
+    let (ret, expr) = match getter {
+        Getter::Copy(inner) => (quote! { #inner }, quote! { value.0 }),
+        Getter::Borrow(inner) => (quote! { &#inner }, quote! { &value.0 }),
+        Getter::Entries(entry) => (quote! { &[#prefix #entry] }, quote! { &value.0 }),
+        Getter::Itself => (quote! { &#prefix #ty_name }, quote! { value }),
+    };
+
+    if optional {
+        quote! {
+            #vis fn #field_name(&self) -> Option<#ret> {
+                match &self.#field_name {
+                    Some(value) => Some(#expr),
+                    None => None,
+                }
+            }
+        }
+    } else {
+        quote! {
+            #vis fn #field_name(&self) -> #ret {
+                let value = &self.#field_name;
+                #expr
+            }
+        }
+    }
+}
+
+fn generate_modules(ctx: &GenerationContext, toml_config: toml::Value) -> TokenStream {
     match toml_config {
         toml::Value::Table(tbl) => {
             let toml_types: &Vec<TomlType> = &tbl
                 .into_iter()
-                .map(|(key, val)| generate_type(key, val))
+                .map(|(key, val)| {
+                    let key_path = vec![key.clone()];
+                    generate_type(
+                        ctx,
+                        &key_path,
+                        key,
+                        val,
+                        &BTreeSet::new(),
+                        &EntryValues::new(),
+                    )
+                })
                 .collect();
 
             let types = toml_types.iter().map(|ty| {
@@ -89,6 +1073,7 @@ fn generate_modules(toml_config: toml::Value) -> TokenStream {
                     name: _,
                     definition,
                     builder,
+                    ..
                 } = ty;
 
                 quote! {
@@ -98,66 +1083,118 @@ fn generate_modules(toml_config: toml::Value) -> TokenStream {
             });
             let complete_struct = toml_types.iter().map(|ty| {
                 let TomlType {
-                    definition:
-                        TomlTypeDefinition {
-                            name: ty_name,
-                            typ: _typ,
-                        },
+                    definition: TomlTypeDefinition { name: ty_name, .. },
                     builder: _,
                     name,
+                    ..
                 }: &TomlType = ty;
                 let field_name = format_ident!("{}", name.to_snake_case());
+                let vis = &ctx.field_vis;
                 quote! {
                     #[serde(rename = #name)]
-                    #field_name: #ty_name
+                    #vis #field_name: #ty_name
                 }
             });
+            let getters = toml_types
+                .iter()
+                .map(|ty| make_getter(ctx, ty, &TokenStream::new(), false));
 
-            let uuid = uuid::Uuid::new_v4().as_bytes().to_vec();
+            let uuid = ctx.type_uuid(&[]);
+            let schema = table_schema(toml_types, &TokenStream::new(), &BTreeSet::new());
+            let attributes = ctx.struct_attributes();
+            let unknown_keys = ctx.unknown_keys();
+            let lenient = ctx.lenient;
+            let vis = &ctx.field_vis;
+            let validate = table_validation(toml_types, &TokenStream::new(), &BTreeSet::new());
+            let default_impl =
+                ctx.table_default_impl(&format_ident!("Root"), toml_types, &BTreeSet::new());
 
             let type_register = toml_types.iter().map(|ty| {
-                let TomlType { name, definition: TomlTypeDefinition { name: ty_name, typ: _ }, builder: _ } = ty;
+                let TomlType {
+                    name,
+                    definition: TomlTypeDefinition { name: ty_name, .. },
+                    ..
+                } = ty;
 
                 let field_name = format_ident!("{}", name.to_snake_case());
                 quote! {
-                    <#ty_name as ::bevy_spicy_data::Config>::register(&self.#field_name, load_context, Some(vec![String::from(#name)]));
-                }                
+                    <#ty_name as ::bevy_spicy_data::Config>::register(
+                        &self.#field_name,
+                        load_context,
+                        Some(vec![String::from(#name)]),
+                        previous.map(|previous| &previous.#field_name),
+                        sub_assets,
+                    );
+                }
             });
 
             let child_assets = toml_types.iter().map(|ty| {
-                let TomlType { name: _, definition: TomlTypeDefinition { name: ty_name, typ: _ }, builder: _ } = ty;
+                let TomlType {
+                    name: _,
+                    definition: TomlTypeDefinition { name: ty_name, .. },
+                    ..
+                } = ty;
 
                 quote! {
                     <#ty_name as ::bevy_spicy_data::Config>::add_asset(app);
-                }                
+                }
             });
 
             quote! {
                 #(#types)*
 
                 #[derive(::bevy_spicy_data::private::serde::Deserialize, Debug, Clone, PartialEq)]
+                #attributes
                 pub struct Root {
                     #(#complete_struct),*
                 }
 
+                #default_impl
+
+                impl Root {
+                    #(#getters)*
+
+                    /// Loads the given file
+                    #vis fn load_from(asset_server: &::bevy_spicy_data::private::AssetServer, file: &str) -> ::bevy_spicy_data::private::Handle<Self> {
+                        asset_server.load(file)
+                    }
+                }
+
                 impl ::bevy_spicy_data::Config for Root {
-                    fn register<'a>(&self, load_context: &'a mut ::bevy_spicy_data::private::LoadContext, _path: Option<Vec<String>>) {
+                    fn register<'a>(&self, load_context: &'a mut ::bevy_spicy_data::private::LoadContext, _path: Option<Vec<String>>, previous: Option<&Self>, sub_assets: &mut Vec<::bevy_spicy_data::private::SubAsset>) {
+                        // The root is always set, so that it can be loaded again once it has been dropped
                         load_context.set_default_asset(::bevy_spicy_data::private::LoadedAsset::new(<Root as Clone>::clone(self)));
 
                         #(#type_register)*
                     }
 
                     fn add_asset(app: &mut ::bevy_spicy_data::private::App) {
-                        use ::bevy_spicy_data::private::AddAsset;
-
-                        app.add_asset::<Self>();
+                        if !::bevy_spicy_data::private::add_asset::<Self>(app) {
+                            return;
+                        }
 
                         #(#child_assets)*
                     }
+
+                    fn schema() -> ::bevy_spicy_data::Schema {
+                        #schema
+                    }
+
+                    fn validate(&self, path: Vec<String>) -> Vec<String> {
+                        #validate
+                    }
+
+                    fn unknown_keys() -> ::bevy_spicy_data::UnknownKeys {
+                        #unknown_keys
+                    }
+
+                    fn lenient() -> bool {
+                        #lenient
+                    }
                 }
 
                 impl ::bevy_spicy_data::private::TypeUuid for Root {
-                    const TYPE_UUID: ::bevy_spicy_data::private::Uuid = ::bevy_spicy_data::private::Uuid::from_bytes([#(#uuid),*]);
+                    const TYPE_UUID: ::bevy_spicy_data::private::Uuid = #uuid;
                 }
             }
         }
@@ -171,61 +1208,377 @@ fn generate_modules(toml_config: toml::Value) -> TokenStream {
     }
 }
 
-fn make_builder(ty_name: &Ident, children: Option<(Vec<TokenStream>, Vec<TokenStream>)>, custom_add_asset: Option<TokenStream>) -> TokenStream {
-    let uuid = uuid::Uuid::new_v4().as_bytes().to_vec();
+/// Generates the schema of a table, `prefix` being the path to the module containing the types of its fields
+fn table_schema(
+    toml_types: &[TomlType],
+    prefix: &TokenStream,
+    optional: &BTreeSet<Vec<String>>,
+) -> TokenStream {
+    let fields = toml_types.iter().map(|ty| {
+        let TomlType { name, definition: TomlTypeDefinition { name: ty_name, .. }, .. } = ty;
+        let optional = optional.contains(&vec![name.clone()]);
+
+        quote! {
+            ::bevy_spicy_data::SchemaField::new(#name, <#prefix #ty_name as ::bevy_spicy_data::Config>::schema(), #optional)
+        }
+    });
+
+    quote! {
+        ::bevy_spicy_data::Schema::Table(vec![#(#fields),*])
+    }
+}
+
+/// Generates the body of `Config::validate` of a table, validating each of its fields
+fn table_validation(
+    toml_types: &[TomlType],
+    prefix: &TokenStream,
+    optional: &BTreeSet<Vec<String>>,
+) -> TokenStream {
+    let fields = toml_types.iter().map(|ty| {
+        let TomlType {
+            name,
+            definition: TomlTypeDefinition { name: ty_name, .. },
+            ..
+        } = ty;
+        let field_name = format_ident!("{}", name.to_snake_case());
+        let validate = quote! {
+            failures.extend(<#prefix #ty_name as ::bevy_spicy_data::Config>::validate(value, {
+                let mut path = path.clone();
+                path.push(String::from(#name));
+                path
+            }));
+        };
+
+        if optional.contains(&vec![name.clone()]) {
+            quote! {
+                if let Some(value) = &self.#field_name {
+                    #validate
+                }
+            }
+        } else {
+            quote! {
+                let value = &self.#field_name;
+                #validate
+            }
+        }
+    });
+
+    if toml_types.is_empty() {
+        return quote! {
+            let _ = path;
+            Vec::new()
+        };
+    }
+
+    quote! {
+        let mut failures = Vec::new();
+        #(#fields)*
+        failures
+    }
+}
+
+/// Generates the label of a sub-asset and the helpers to load it
+///
+/// Key paths inside of arrays of tables contain a `*` for each entry, which is filled in with the given indices.
+fn make_label(ctx: &GenerationContext, key_path: &[String], ty_name: &Ident) -> TokenStream {
+    let vis = &ctx.field_vis;
+    let label = key_path.join(".");
+    let asset_server = quote! { ::bevy_spicy_data::private::AssetServer };
+    let handle = quote! { ::bevy_spicy_data::private::Handle<Self> };
+
+    let stars = key_path.iter().filter(|key| *key == "*").count();
+    if stars == 0 {
+        return quote! {
+            impl #ty_name {
+                /// The label of this sub-asset, the part of its asset path after the `#`
+                #vis const LABEL: &'static str = #label;
+
+                /// Loads this sub-asset from the given file
+                #vis fn load_from(asset_server: &#asset_server, file: &str) -> #handle {
+                    asset_server.load(format!("{}#{}", file, Self::LABEL).as_str())
+                }
+            }
+        };
+    }
+
+    let format = key_path
+        .iter()
+        .map(|key| match key.as_str() {
+            "*" => String::from("{}"),
+            key => key.replace('{', "{{").replace('}', "}}"),
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+    let indices = (0..stars).map(syn::Index::from);
+
+    quote! {
+        impl #ty_name {
+            /// The label of this sub-asset, with a `*` for the index of each entry of an array of tables
+            #vis const LABEL: &'static str = #label;
+
+            /// Returns the label of this sub-asset in the given entries of the arrays of tables containing it
+            #vis fn label_at(indices: [usize; #stars]) -> String {
+                format!(#format, #(indices[#indices]),*)
+            }
+
+            /// Loads this sub-asset, in the given entries of the arrays of tables containing it, from the given file
+            #vis fn load_from(asset_server: &#asset_server, file: &str, indices: [usize; #stars]) -> #handle {
+                asset_server.load(format!("{}#{}", file, Self::label_at(indices)).as_str())
+            }
+        }
+    }
+}
+
+fn make_builder(
+    ctx: &GenerationContext,
+    key_path: &[String],
+    ty_name: &Ident,
+    schema: TokenStream,
+    validate: Option<TokenStream>,
+    children: Option<(Vec<TokenStream>, Vec<TokenStream>)>,
+) -> TokenStream {
+    let uuid = ctx.type_uuid(key_path);
+    let label = make_label(ctx, key_path, ty_name);
+    let validate = match validate {
+        Some(validate) => quote! {
+            fn validate(&self, path: Vec<String>) -> Vec<String> {
+                #validate
+            }
+        },
+        None => quote! {
+            fn validate(&self, _path: Vec<String>) -> Vec<String> {
+                Vec::new()
+            }
+        },
+    };
+    let unknown_keys = ctx.unknown_keys();
+    let lenient = ctx.lenient;
     let (register, add_asset) = if let Some((register, add_asset)) = children {
         (register, add_asset)
     } else {
         (vec![], vec![])
     };
 
-    let custom_add_asset = custom_add_asset.unwrap_or_default();
-
     quote! {
         impl ::bevy_spicy_data::Config for #ty_name {
-            fn register<'a>(&self, load_context: &'a mut ::bevy_spicy_data::private::LoadContext, path: Option<Vec<String>>) {
+            fn register<'a>(&self, load_context: &'a mut ::bevy_spicy_data::private::LoadContext, path: Option<Vec<String>>, previous: Option<&Self>, sub_assets: &mut Vec<::bevy_spicy_data::private::SubAsset>) {
                 let asset_path = path.as_ref().unwrap().join(".");
-                let handle = load_context.set_labeled_asset(&asset_path, ::bevy_spicy_data::private::LoadedAsset::new(<Self as Clone>::clone(self)));
+                let handle: ::bevy_spicy_data::private::Handle<Self> = if previous == Some(self) {
+                    // Unchanged values are not set again, but are still part of the file
+                    let id = ::bevy_spicy_data::private::AssetPath::new_ref(load_context.path(), Some(&asset_path));
+                    load_context.get_handle(id)
+                } else {
+                    load_context.set_labeled_asset(&asset_path, ::bevy_spicy_data::private::LoadedAsset::new(<Self as Clone>::clone(self)))
+                };
 
-                ::std::mem::forget(handle);
+                // Kept alive until the value disappears from the file, or the root is dropped
+                sub_assets.push(::bevy_spicy_data::private::SubAsset::new(handle));
 
                 #(#register)*
             }
 
 
             fn add_asset(app: &mut ::bevy_spicy_data::private::App) {
-                use ::bevy_spicy_data::private::AddAsset;
-                
-                app.add_asset::<Self>();
+                if !::bevy_spicy_data::private::add_asset::<Self>(app) {
+                    return;
+                }
 
                 #(#add_asset)*
+            }
+
+            fn schema() -> ::bevy_spicy_data::Schema {
+                #schema
+            }
+
+            #validate
+
+            fn unknown_keys() -> ::bevy_spicy_data::UnknownKeys {
+                #unknown_keys
+            }
 
-                #custom_add_asset
+            fn lenient() -> bool {
+                #lenient
             }
         }
 
         impl ::bevy_spicy_data::private::TypeUuid for #ty_name {
-            const TYPE_UUID: ::bevy_spicy_data::private::Uuid = ::bevy_spicy_data::private::Uuid::from_bytes([#(#uuid),*]);
+            const TYPE_UUID: ::bevy_spicy_data::private::Uuid = #uuid;
         }
+
+        #label
     }
 }
 
-fn generate_type(name: String, toml_config: toml::Value) -> TomlType {
+/// Returns the first value of an array, aborting if it mixes values of different types
+fn array_first_value<'a>(name: &str, values: &'a [toml::Value]) -> Option<&'a toml::Value> {
+    let first = values.first()?;
 
+    if let Some(other) = values.iter().find(|val| val.type_str() != first.type_str()) {
+        proc_macro_error::abort_call_site!(
+            "The array `{}` mixes values of type {} and {}", name, first.type_str(), other.type_str();
+            note = "Only arrays whose values all have the same type are supported"
+        );
+    }
 
-    match toml_config {
-        toml::Value::String(_) => {
-            let ident = format_ident!("{}", name.to_camel_case());
+    Some(first)
+}
 
-            let builder = make_builder(&ident, None, Some(quote! {
-                app.add_system_to_stage(::bevy_spicy_data::SyncStage, ::bevy_spicy_data::UiDataText::<Self>::when_inserted);
-                app.add_system_to_stage(::bevy_spicy_data::SyncStage, ::bevy_spicy_data::UiDataText::<Self>::keep_in_sync);
-            }));
+/// The values found in every entry of an array of tables, by key path relative to the entries
+///
+/// Merging the entries only keeps a single value for each key, these are used to check all of them.
+type EntryValues = BTreeMap<Vec<String>, Vec<toml::Value>>;
+
+/// Merges all entries of an array of tables into a single table containing every key
+///
+/// The returned set contains the key paths (relative to the entries) that are missing in
+/// at least one of the entries, and thus have to be optional.
+fn merge_tables(
+    name: &str,
+    entries: Vec<toml::value::Table>,
+) -> (toml::value::Table, BTreeSet<Vec<String>>, EntryValues) {
+    let entry_count = entries.len();
+    let mut values: BTreeMap<String, Vec<toml::Value>> = BTreeMap::new();
+
+    for entry in entries {
+        for (key, val) in entry {
+            values.entry(key).or_default().push(val);
+        }
+    }
+
+    let mut merged = toml::value::Table::new();
+    let mut optional = BTreeSet::new();
+    let mut entry_values = EntryValues::new();
+
+    for (key, vals) in values {
+        if vals.len() < entry_count {
+            optional.insert(vec![key.clone()]);
+        }
+
+        let (value, nested_optional, nested_values) =
+            merge_values(&format!("{}.{}", name, key), vals);
+
+        optional.extend(nested_optional.into_iter().map(|mut path| {
+            path.insert(0, key.clone());
+            path
+        }));
+        entry_values.extend(nested_values.into_iter().map(|(mut path, values)| {
+            path.insert(0, key.clone());
+            (path, values)
+        }));
+        merged.insert(key, value);
+    }
+
+    (merged, optional, entry_values)
+}
+
+/// Merges the values found for the same key in different entries of an array of tables
+///
+/// Arrays are merged into one holding all of their values, for other values all of them are returned
+/// with an empty key path.
+fn merge_values(
+    name: &str,
+    values: Vec<toml::Value>,
+) -> (toml::Value, BTreeSet<Vec<String>>, EntryValues) {
+    let type_strs: BTreeSet<&str> = values.iter().map(|val| val.type_str()).collect();
+
+    match type_strs.into_iter().collect::<Vec<_>>().as_slice() {
+        ["table"] => {
+            let tables = values
+                .into_iter()
+                .filter_map(|val| match val {
+                    toml::Value::Table(tbl) => Some(tbl),
+                    _ => None,
+                })
+                .collect();
+            let (merged, optional, entry_values) = merge_tables(name, tables);
+
+            (toml::Value::Table(merged), optional, entry_values)
+        }
+        ["array"] => {
+            let elements = values
+                .into_iter()
+                .filter_map(|val| match val {
+                    toml::Value::Array(arr) => Some(arr),
+                    _ => None,
+                })
+                .flatten()
+                .collect();
+
+            (
+                toml::Value::Array(elements),
+                BTreeSet::new(),
+                EntryValues::new(),
+            )
+        }
+        [_] | ["float", "integer"] => {
+            let value = values
+                .iter()
+                .find(|val| val.is_float())
+                .unwrap_or(&values[0])
+                .clone();
+            let mut entry_values = EntryValues::new();
+            entry_values.insert(vec![], values);
+
+            (value, BTreeSet::new(), entry_values)
+        }
+        found => {
+            proc_macro_error::abort_call_site!(
+                "The key `{}` has different types across the entries of its array: {}", name, found.join(", ");
+                note = "Each key of an array of tables needs to have the same type in every entry"
+            );
+        }
+    }
+}
+
+fn generate_leaf(
+    ctx: &GenerationContext,
+    key_path: &[String],
+    name: String,
+    leaf: LeafType,
+    values: &[toml::Value],
+    is_array: bool,
+) -> TomlType {
+    let ident = format_ident!("{}", name.to_camel_case());
+    let ty = leaf.type_tokens();
+    let validate = ctx.validation(key_path, &leaf, values, is_array);
+    let typ = if is_array {
+        quote! {(pub Vec<#ty>);}
+    } else {
+        quote! {(pub #ty);}
+    };
+    let schema = match &leaf {
+        LeafType::String => quote! { ::bevy_spicy_data::Schema::String },
+        LeafType::Integer(_) => quote! { ::bevy_spicy_data::Schema::Integer },
+        LeafType::Float(_) => quote! { ::bevy_spicy_data::Schema::Float },
+        LeafType::Boolean => quote! { ::bevy_spicy_data::Schema::Boolean },
+    };
+    let schema = if is_array {
+        quote! { ::bevy_spicy_data::Schema::Array(Box::new(#schema)) }
+    } else {
+        schema
+    };
+    let getter = match (&leaf, is_array) {
+        (_, true) => Getter::Borrow(quote! { [#ty] }),
+        (LeafType::String, false) => Getter::Borrow(quote! { str }),
+        (_, false) => Getter::Copy(ty),
+    };
+    let display = if is_array {
+        TokenStream::new()
+    } else {
+        let numeric = matches!(leaf, LeafType::Integer(_) | LeafType::Float(_));
+        ui_value_impl(&ident, numeric)
+    };
+
+    match leaf {
+        LeafType::String if !is_array => {
+            let builder = make_builder(ctx, key_path, &ident, schema, validate, None);
 
             TomlType {
                 name,
+                getter,
                 builder: quote! {
                     #builder
+                    #display
 
                     impl ::std::convert::AsRef<str> for #ident {
                         fn as_ref(&self) -> &str {
@@ -235,65 +1588,279 @@ fn generate_type(name: String, toml_config: toml::Value) -> TomlType {
                 },
                 definition: TomlTypeDefinition {
                     name: ident,
-                    typ: quote! {(pub String);},
+                    attributes: TokenStream::new(),
+                    typ,
                 },
             }
         }
-        toml::Value::Integer(_) => {
-            let ident = format_ident!("{}", name.to_camel_case());
+        LeafType::Integer(integer) if integer != "i64" => {
+            let builder = make_builder(ctx, key_path, &ident, schema, validate, None);
+            let key_path = key_path.join(".");
+
+            let (source, conversion) = if is_array {
+                (
+                    "Vec<i64>",
+                    quote! {
+                        impl ::std::convert::TryFrom<Vec<i64>> for #ident {
+                            type Error = String;
+
+                            fn try_from(values: Vec<i64>) -> Result<Self, Self::Error> {
+                                values
+                                    .into_iter()
+                                    .enumerate()
+                                    .map(|(index, value)| {
+                                        ::bevy_spicy_data::private::integer_in_range(value, &format!("{}[{}]", #key_path, index))
+                                    })
+                                    .collect::<Result<_, _>>()
+                                    .map(#ident)
+                            }
+                        }
+                    },
+                )
+            } else {
+                (
+                    "i64",
+                    quote! {
+                        impl ::std::convert::TryFrom<i64> for #ident {
+                            type Error = String;
+
+                            fn try_from(value: i64) -> Result<Self, Self::Error> {
+                                ::bevy_spicy_data::private::integer_in_range(value, #key_path).map(#ident)
+                            }
+                        }
+                    },
+                )
+            };
 
             TomlType {
                 name,
-                builder: make_builder(&ident, None, None),
+                getter,
+                builder: quote! {
+                    #builder
+                    #conversion
+                    #display
+                },
                 definition: TomlTypeDefinition {
                     name: ident,
-                    typ: quote! {(pub u64);},
+                    attributes: quote! { #[serde(try_from = #source)] },
+                    typ,
                 },
             }
         }
-        toml::Value::Float(_) => {
-            let ident = format_ident!("{}", name.to_camel_case());
+        _ => {
+            let builder = make_builder(ctx, key_path, &ident, schema, validate, None);
 
             TomlType {
                 name,
-                builder: make_builder(&ident, None, None),
+                getter,
+                builder: quote! {
+                    #builder
+                    #display
+                },
                 definition: TomlTypeDefinition {
                     name: ident,
-                    typ: quote! {(pub f64);},
+                    attributes: TokenStream::new(),
+                    typ,
                 },
             }
         }
-        toml::Value::Boolean(_) => {
+    }
+}
+
+/// Generates the `Display` and `UiValue` impls allowing a single value to be shown through `UiDataText`
+///
+/// The `Display` impl forwards the formatter, so that the precision given to `UiDataText` applies to numbers.
+fn ui_value_impl(ty_name: &Ident, numeric: bool) -> TokenStream {
+    quote! {
+        impl ::std::fmt::Display for #ty_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl ::bevy_spicy_data::UiValue for #ty_name {
+            const NUMERIC: bool = #numeric;
+        }
+    }
+}
+
+fn generate_type(
+    ctx: &GenerationContext,
+    key_path: &[String],
+    name: String,
+    toml_config: toml::Value,
+    optional: &BTreeSet<Vec<String>>,
+    entry_values: &EntryValues,
+) -> TomlType {
+    let value = match &toml_config {
+        toml::Value::Table(_) => None,
+        value => Some(value.clone()),
+    };
+
+    let mut ty = match toml_config {
+        value @ toml::Value::String(_)
+        | value @ toml::Value::Integer(_)
+        | value @ toml::Value::Float(_)
+        | value @ toml::Value::Boolean(_) => {
+            // Inside of arrays of tables, the values of all entries are checked
+            let values = entry_values
+                .get(&Vec::new())
+                .map_or_else(|| vec![value.clone()], Clone::clone);
+            let leaf = ctx.leaf_type(key_path, &values);
+            generate_leaf(ctx, key_path, name, leaf, &values, false)
+        }
+        toml::Value::Datetime(_) => {
+            ctx.reject_type(key_path, "a datetime");
+
             let ident = format_ident!("{}", name.to_camel_case());
+            let datetime = quote! { ::bevy_spicy_data::private::toml::value::Datetime };
+
+            let display = ui_value_impl(&ident, false);
+            let builder = make_builder(
+                ctx,
+                key_path,
+                &ident,
+                quote! { ::bevy_spicy_data::Schema::Datetime },
+                None,
+                None,
+            );
 
             TomlType {
                 name,
-                builder: make_builder(&ident, None, None),
+                builder: quote! {
+                    #builder
+                    #display
+                },
                 definition: TomlTypeDefinition {
                     name: ident,
-                    typ: quote! {(pub bool);},
+                    attributes: TokenStream::new(),
+                    typ: quote! {(#[serde(deserialize_with = "::bevy_spicy_data::private::datetime")] pub #datetime);},
                 },
+                getter: Getter::Borrow(datetime),
             }
         }
-        toml::Value::Datetime(_) => {
-            let ident = format_ident!("{}", name.to_camel_case());
+        toml::Value::Array(values)
+            if matches!(
+                array_first_value(&name, &values),
+                Some(toml::Value::Table(_))
+            ) =>
+        {
+            ctx.reject_type(key_path, "an array of tables");
+
+            let tables = values
+                .into_iter()
+                .filter_map(|val| match val {
+                    toml::Value::Table(tbl) => Some(tbl),
+                    _ => None,
+                })
+                .collect();
+            let (merged, entry_optional, entry_values) = merge_tables(&name, tables);
+
+            let entry = generate_type(
+                ctx,
+                &[key_path, &[String::from("*")]].concat(),
+                String::from("entry"),
+                toml::Value::Table(merged),
+                &entry_optional,
+                &entry_values,
+            );
+            let TomlType {
+                name: _,
+                definition,
+                builder: entry_builder,
+                ..
+            } = &entry;
+            let entry_ident = &definition.name;
+
+            let mod_ident = format_ident!("{}", name.to_snake_case());
+            let ty_ident = format_ident!("{}", name.to_camel_case());
+            let config_builder = make_builder(
+                ctx,
+                key_path,
+                &ty_ident,
+                quote! {
+                    ::bevy_spicy_data::Schema::Array(Box::new(
+                        <#mod_ident::#entry_ident as ::bevy_spicy_data::Config>::schema()
+                    ))
+                },
+                Some(quote! {
+                    self.0
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(index, entry)| {
+                            let mut path = path.clone();
+                            path.push(index.to_string());
+                            <#mod_ident::#entry_ident as ::bevy_spicy_data::Config>::validate(entry, path)
+                        })
+                        .collect()
+                }),
+                Some((
+                    vec![quote! {
+                        for (index, entry) in self.0.iter().enumerate() {
+                            <#mod_ident::#entry_ident as ::bevy_spicy_data::Config>::register(entry, load_context, Some({
+                                let mut path: Vec<String> = path.as_ref().unwrap().clone();
+                                path.push(index.to_string());
+                                path
+                            }), previous.and_then(|previous| previous.0.get(index)), sub_assets);
+                        }
+                    }],
+                    vec![quote! {
+                        <#mod_ident::#entry_ident as ::bevy_spicy_data::Config>::add_asset(app);
+                    }],
+                )),
+            );
 
             TomlType {
                 name,
-                builder: make_builder(&ident, None, None),
                 definition: TomlTypeDefinition {
-                    name: ident,
-                    typ: quote! {(pub ::bevy_spicy_data::private::toml::Date);},
+                    name: ty_ident,
+                    attributes: TokenStream::new(),
+                    typ: quote! {(pub Vec<#mod_ident::#entry_ident>);},
+                },
+                getter: Getter::Entries(quote! { #mod_ident::#entry_ident }),
+                builder: quote! {
+                    #config_builder
+
+                    pub mod #mod_ident {
+                        #entry_builder
+                        #definition
+                    }
                 },
             }
         }
-        toml::Value::Array(_) => {
-            proc_macro_error::abort_call_site!("Arrays are not supported");
+        toml::Value::Array(values) => {
+            array_first_value(&name, &values);
+
+            // Empty arrays take the chosen type
+            let leaf = ctx.leaf_type(key_path, &values);
+            generate_leaf(ctx, key_path, name, leaf, &values, true)
         }
         toml::Value::Table(tbl) => {
+            ctx.reject_type(key_path, "a table");
+
             let toml_types: &Vec<TomlType> = &tbl
                 .into_iter()
-                .map(|(key, val)| generate_type(key, val))
+                .map(|(key, val)| {
+                    let child_optional = optional
+                        .iter()
+                        .filter(|path| path.len() > 1 && path[0] == key)
+                        .map(|path| path[1..].to_vec())
+                        .collect();
+                    let child_values = entry_values
+                        .iter()
+                        .filter(|(path, _)| path.first() == Some(&key))
+                        .map(|(path, values)| (path[1..].to_vec(), values.clone()))
+                        .collect();
+
+                    generate_type(
+                        ctx,
+                        &[key_path, std::slice::from_ref(&key)].concat(),
+                        key,
+                        val,
+                        &child_optional,
+                        &child_values,
+                    )
+                })
                 .collect();
 
             let types = toml_types.iter().map(|ty| {
@@ -301,6 +1868,7 @@ fn generate_type(name: String, toml_config: toml::Value) -> TomlType {
                     name: _,
                     definition,
                     builder,
+                    ..
                 } = ty;
 
                 quote! {
@@ -312,57 +1880,196 @@ fn generate_type(name: String, toml_config: toml::Value) -> TomlType {
             let mod_ident = format_ident!("{}", name.to_snake_case());
             let complete_struct = toml_types.iter().map(|ty| {
                 let TomlType {
-                    definition:
-                        TomlTypeDefinition {
-                            name: ty_name,
-                            typ: _,
-                        },
+                    definition: TomlTypeDefinition { name: ty_name, .. },
                     builder: _,
                     name,
+                    ..
                 }: &TomlType = ty;
                 let field_name = format_ident!("{}", name.to_snake_case());
-                quote! {
-                    #[serde(rename = #name)]
-                    #field_name: #mod_ident::#ty_name
+                let vis = &ctx.field_vis;
+                if optional.contains(&vec![name.clone()]) {
+                    quote! {
+                        #[serde(rename = #name, default)]
+                        #vis #field_name: Option<#mod_ident::#ty_name>
+                    }
+                } else {
+                    quote! {
+                        #[serde(rename = #name)]
+                        #vis #field_name: #mod_ident::#ty_name
+                    }
                 }
             });
+            let getters = toml_types.iter().map(|ty| {
+                make_getter(
+                    ctx,
+                    ty,
+                    &quote! { #mod_ident:: },
+                    optional.contains(&vec![ty.name.clone()]),
+                )
+            });
 
             let ty_ident = format_ident!("{}", name.to_camel_case());
-            let config_builder = make_builder(&ty_ident, Some((toml_types.iter().map(|ty| {
-                let TomlType { name: child_name, definition: TomlTypeDefinition { name: ty_name, typ: _ }, builder: _ } = ty;
+            let schema = table_schema(toml_types, &quote! { #mod_ident:: }, optional);
+            let default_impl = ctx.table_default_impl(&ty_ident, toml_types, optional);
+            let validate = table_validation(toml_types, &quote! { #mod_ident:: }, optional);
+            let config_builder = make_builder(ctx, key_path, &ty_ident, schema, Some(validate), Some((toml_types.iter().map(|ty| {
+                let TomlType { name: child_name, definition: TomlTypeDefinition { name: ty_name, .. }, .. } = ty;
 
                 let field_name = format_ident!("{}", child_name.to_snake_case());
-                quote! {
-                    <#mod_ident::#ty_name as ::bevy_spicy_data::Config>::register(&self.#field_name, load_context, Some({
+                let register = quote! {
+                    <#mod_ident::#ty_name as ::bevy_spicy_data::Config>::register(value, load_context, Some({
                         let mut path: Vec<String> = path.as_ref().unwrap().clone();
                         path.push(String::from(#child_name));
                         path
-                    }));
+                    }), previous_value, sub_assets);
+                };
+
+                if optional.contains(&vec![child_name.clone()]) {
+                    quote! {
+                        if let Some(value) = &self.#field_name {
+                            let previous_value = previous.and_then(|previous| previous.#field_name.as_ref());
+                            #register
+                        }
+                    }
+                } else {
+                    quote! {
+                        let value = &self.#field_name;
+                        let previous_value = previous.map(|previous| &previous.#field_name);
+                        #register
+                    }
                 }
             }).collect(),toml_types.iter().map(|ty| {
-                let TomlType { name: _child_name, definition: TomlTypeDefinition { name: ty_name, typ: _ }, builder: _ } = ty;
+                let TomlType { name: _child_name, definition: TomlTypeDefinition { name: ty_name, .. }, .. } = ty;
 
                 quote! {
                     <#mod_ident::#ty_name as ::bevy_spicy_data::Config>::add_asset(app);
                 }
             }).collect(),
-            )), None);
+            )));
             TomlType {
                 name,
                 definition: TomlTypeDefinition {
-                    name: ty_ident,
+                    name: ty_ident.clone(),
+                    attributes: ctx.struct_attributes(),
                     typ: quote! {{
                         #(#complete_struct),*
                     }},
                 },
                 builder: quote! {
                     #config_builder
+                    #default_impl
+
+                    impl #ty_ident {
+                        #(#getters)*
+                    }
 
                     pub mod #mod_ident {
                         #(#types)*
                     }
                 },
+                getter: Getter::Itself,
+            }
+        }
+    };
+
+    if let Some(value) = value {
+        let default_impl = ctx.default_impl(key_path, &ty.definition.name, &value);
+        ty.builder.extend(default_impl);
+    }
+
+    ty
+}
+
+/// Generates the expression building the given value at runtime
+fn value_tokens(value: &toml::Value) -> TokenStream {
+    let toml = quote! { ::bevy_spicy_data::private::toml };
+
+    match value {
+        toml::Value::String(string) => quote! { #toml::Value::String(String::from(#string)) },
+        toml::Value::Integer(integer) => quote! { #toml::Value::Integer(#integer) },
+        toml::Value::Float(float) => {
+            let float = if float.is_nan() {
+                quote! { f64::NAN }
+            } else if float.is_infinite() && *float > 0.0 {
+                quote! { f64::INFINITY }
+            } else if float.is_infinite() {
+                quote! { f64::NEG_INFINITY }
+            } else {
+                quote! { #float }
+            };
+            quote! { #toml::Value::Float(#float) }
+        }
+        toml::Value::Boolean(boolean) => quote! { #toml::Value::Boolean(#boolean) },
+        toml::Value::Datetime(datetime) => {
+            let datetime = datetime.to_string();
+            quote! {
+                #toml::Value::Datetime(match #datetime.parse() {
+                    Ok(datetime) => datetime,
+                    Err(err) => panic!("Could not read the datetime `{}`: {}", #datetime, err),
+                })
             }
         }
+        toml::Value::Array(values) => {
+            let values = values.iter().map(value_tokens);
+            quote! { #toml::Value::Array(vec![#(#values),*]) }
+        }
+        toml::Value::Table(table) => {
+            let keys = table.keys();
+            let values = table.values().map(value_tokens);
+            quote! {
+                #toml::Value::Table(vec![#((String::from(#keys), #values)),*].into_iter().collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(source: &str) -> Vec<toml::value::Table> {
+        let document: toml::value::Table = toml::from_str(source).expect("the source parses");
+        document["waves"]
+            .as_array()
+            .expect("waves is an array")
+            .iter()
+            .filter_map(|entry| entry.as_table().cloned())
+            .collect()
+    }
+
+    #[test]
+    fn merged_entries_keep_the_values_of_every_entry() {
+        let (merged, _, values) = merge_tables(
+            "waves",
+            entries("[[waves]]\ncount = 3\n[waves.boss]\nhp = 10\n[[waves]]\ncount = 300\n[waves.boss]\nhp = 20.5\n"),
+        );
+
+        assert_eq!(merged["count"], toml::Value::Integer(3));
+        assert_eq!(
+            values[&vec![String::from("count")]],
+            vec![toml::Value::Integer(3), toml::Value::Integer(300)]
+        );
+        assert_eq!(merged["boss"]["hp"], toml::Value::Float(20.5));
+        assert_eq!(
+            values[&vec![String::from("boss"), String::from("hp")]],
+            vec![toml::Value::Integer(10), toml::Value::Float(20.5)]
+        );
+    }
+
+    #[test]
+    fn merged_entries_mark_missing_keys_as_optional() {
+        let (merged, optional, values) = merge_tables(
+            "waves",
+            entries("[[waves]]\ncount = 3\nboss = \"big\"\nspawns = [1]\n[[waves]]\ncount = 5\nspawns = [2, 3]\n"),
+        );
+
+        assert_eq!(
+            optional,
+            vec![vec![String::from("boss")]].into_iter().collect()
+        );
+        assert_eq!(values[&vec![String::from("boss")]].len(), 1);
+        // Arrays are merged into one holding all of their values instead
+        assert_eq!(merged["spawns"].as_array().map(Vec::len), Some(3));
+        assert!(!values.contains_key(&vec![String::from("spawns")]));
     }
 }
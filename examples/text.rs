@@ -10,7 +10,11 @@ data_config!(pub config, "assets/game.config");
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugin(bevy_spicy_data::TomlConfigPlugin::<config::Root>::default())
+        .add_plugin(
+            bevy_spicy_data::TomlConfigPlugin::<config::Root>::default()
+                .with_error_overlay("Share-Regular.ttf")
+                .with_ui_text::<config::system::Debug>(),
+        )
         .add_startup_system(setup)
         .add_system(text_update_system)
         .run();
@@ -21,8 +25,8 @@ struct TomlText(Handle<config::display::Text>);
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     asset_server.watch_for_changes().unwrap();
 
-    let toml_asset_handle = asset_server.load("game.config#display some.text");
-    let text_handle = asset_server.load("game.config#system.debug");
+    let toml_asset_handle = config::display::Text::load_from(&asset_server, "game.config");
+    let text_handle = config::system::Debug::load_from(&asset_server, "game.config");
     // UI camera
     commands.spawn_bundle(UiCameraBundle::default());
     // Text with one section
@@ -86,7 +90,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             ),
             ..Default::default()
         })
-        .insert(UiDataText::<config::system::Debug>(text_handle));
+        .insert(UiDataText::new(text_handle).with_template("Debug: {placeholder}"));
 }
 
 fn text_update_system(
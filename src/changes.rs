@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use bevy::{asset::HandleId, prelude::*};
+
+use crate::Config;
+
+/// Sent whenever a config, or any part of it, is loaded with a different value than before
+///
+/// As every table and value of a config is its own sub-asset, this can be used to react to a single value:
+/// `EventReader<ConfigChanged<config::player::MaxHealth>>` only sees changes of `player.max_health`.
+/// The events are only sent for the types given to
+/// [`TomlConfigPlugin::with_change_events`](crate::TomlConfigPlugin::with_change_events).
+#[derive(Debug, Clone)]
+pub struct ConfigChanged<T: Config> {
+    /// The (weak) handle of the asset that changed
+    pub handle: Handle<T>,
+    /// The previous value, `None` if it has just been loaded for the first time
+    pub old: Option<T>,
+    /// The new value
+    pub new: T,
+}
+
+/// The values last sent as `new` in a [`ConfigChanged`] event, to send along as `old` when they change
+struct PreviousValues<T>(HashMap<HandleId, T>);
+
+impl<T: Config> ConfigChanged<T> {
+    /// Adds the event along with the system sending it, unless another plugin already did
+    pub(crate) fn add(app: &mut App) {
+        if app.world.contains_resource::<PreviousValues<T>>() {
+            return;
+        }
+
+        app.insert_resource(PreviousValues::<T>(HashMap::new()))
+            .add_event::<ConfigChanged<T>>()
+            .add_system_to_stage(CoreStage::PreUpdate, Self::send_events);
+    }
+
+    fn send_events(
+        mut previous: ResMut<PreviousValues<T>>,
+        mut asset_events: EventReader<AssetEvent<T>>,
+        assets: Res<Assets<T>>,
+        mut events: EventWriter<ConfigChanged<T>>,
+    ) {
+        for event in asset_events.iter() {
+            match event {
+                AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                    let new = match assets.get(handle) {
+                        Some(new) => new,
+                        None => continue,
+                    };
+
+                    let old = previous.0.insert(handle.id, new.clone());
+                    if old.as_ref() != Some(new) {
+                        events.send(ConfigChanged {
+                            handle: handle.clone_weak(),
+                            old,
+                            new: new.clone(),
+                        });
+                    }
+                }
+                AssetEvent::Removed { handle } => {
+                    previous.0.remove(&handle.id);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use bevy::prelude::*;
+
+use crate::{overlay::ErrorOverlay, Config, SchemaDrift};
+
+/// An error that occurred while loading a config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    /// The path of the file, relative to the asset folder
+    pub path: PathBuf,
+    /// The line and column at which the error occurred, both starting at 1, if known
+    pub location: Option<(usize, usize)>,
+    /// What went wrong
+    pub message: String,
+    /// The lines of the file leading up to the error, with the column marked, if known
+    pub snippet: Option<String>,
+    /// How the file differs from the one the config was generated from, if it does
+    ///
+    /// When the differences explain the error, they are also its message.
+    pub drift: Option<Box<SchemaDrift>>,
+}
+
+impl ConfigError {
+    pub(crate) fn new(path: &Path, message: impl ToString) -> Self {
+        ConfigError {
+            path: path.to_owned(),
+            location: None,
+            message: message.to_string(),
+            snippet: None,
+            drift: None,
+        }
+    }
+
+    pub(crate) fn at(mut self, line: usize, column: usize) -> Self {
+        // Some parsers already mention the location in their message
+        let suffix = format!(" at line {} column {}", line, column);
+        if let Some(message) = self.message.strip_suffix(&suffix) {
+            self.message = message.to_owned();
+        }
+
+        self.location = Some((line, column));
+        self
+    }
+
+    pub(crate) fn with_drift(mut self, drift: SchemaDrift) -> Self {
+        self.drift = Some(Box::new(drift));
+        self
+    }
+
+    pub(crate) fn with_snippet(mut self, source: &[u8]) -> Self {
+        let (line, column) = match self.location {
+            Some(location) => location,
+            None => return self,
+        };
+
+        let source = String::from_utf8_lossy(source);
+        let first = line.saturating_sub(2).max(1);
+        let width = line.to_string().len();
+
+        let mut snippet = String::new();
+        for (number, text) in source.lines().enumerate().map(|(i, text)| (i + 1, text)) {
+            if number < first {
+                continue;
+            }
+            if number > line {
+                break;
+            }
+            snippet.push_str(&format!("{:>width$} | {}\n", number, text, width = width));
+        }
+        snippet.push_str(&format!(
+            "{:>width$} | {:>column$}",
+            "",
+            "^",
+            width = width,
+            column = column
+        ));
+
+        self.snippet = Some(snippet);
+        self
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Could not load {}", self.path.display())?;
+        if let Some((line, column)) = self.location {
+            write!(f, " (line {}, column {})", line, column)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Sent whenever a file could not be loaded as the config `T`
+///
+/// The previously loaded config and all of its sub-assets stay in place,
+/// so the game keeps running on the last good values until the file is fixed.
+#[derive(Debug, Clone)]
+pub struct ConfigLoadFailed<T> {
+    /// What went wrong
+    pub error: ConfigError,
+    kind: PhantomData<fn() -> T>,
+}
+
+impl<T: Config> ConfigLoadFailed<T> {
+    pub(crate) fn send_events(
+        results: Res<LoadResults<T>>,
+        mut events: EventWriter<ConfigLoadFailed<T>>,
+        mut overlay: Option<ResMut<ErrorOverlay>>,
+    ) {
+        for result in results.take() {
+            match result {
+                Ok(path) => {
+                    if let Some(overlay) = overlay.as_mut() {
+                        overlay.loaded(&path);
+                    }
+                }
+                Err(error) => {
+                    if let Some(overlay) = overlay.as_mut() {
+                        overlay.failed(error.clone());
+                    }
+
+                    events.send(ConfigLoadFailed {
+                        error,
+                        kind: PhantomData,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// The outcomes of the loaders of `T`, waiting to be sent as events
+pub(crate) struct LoadResults<T> {
+    results: Arc<Mutex<Vec<Result<PathBuf, ConfigError>>>>,
+    kind: PhantomData<fn() -> T>,
+}
+
+impl<T> LoadResults<T> {
+    pub(crate) fn push(&self, result: Result<PathBuf, ConfigError>) {
+        if let Ok(mut results) = self.results.lock() {
+            results.push(result);
+        }
+    }
+
+    fn take(&self) -> Vec<Result<PathBuf, ConfigError>> {
+        match self.results.lock() {
+            Ok(mut results) => std::mem::take(&mut *results),
+            Err(_) => vec![],
+        }
+    }
+}
+
+impl<T> Clone for LoadResults<T> {
+    fn clone(&self) -> Self {
+        Self {
+            results: Arc::clone(&self.results),
+            kind: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for LoadResults<T> {
+    fn default() -> Self {
+        Self {
+            results: Default::default(),
+            kind: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for LoadResults<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoadResults").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_is_not_repeated_in_the_message() {
+        let error = ConfigError::new(
+            Path::new("game.config"),
+            "expected a value at line 3 column 9",
+        )
+        .at(3, 9);
+        assert_eq!(error.message, "expected a value");
+        assert_eq!(
+            error.to_string(),
+            "Could not load game.config (line 3, column 9): expected a value"
+        );
+    }
+
+    #[test]
+    fn other_locations_in_the_message_are_kept() {
+        let error = ConfigError::new(
+            Path::new("game.config"),
+            "expected a value at line 2 column 1",
+        )
+        .at(3, 9);
+        assert_eq!(error.message, "expected a value at line 2 column 1");
+    }
+
+    #[test]
+    fn snippet_shows_the_lines_up_to_the_error() {
+        let source = b"a = 1\nb = 2\nc = 3\nd = ?\ne = 5";
+        let error = ConfigError::new(Path::new("game.config"), "expected a value")
+            .at(4, 5)
+            .with_snippet(source);
+        assert_eq!(
+            error.snippet.as_deref(),
+            Some("2 | b = 2\n3 | c = 3\n4 | d = ?\n  |     ^")
+        );
+    }
+
+    #[test]
+    fn snippet_aligns_line_numbers_of_different_widths() {
+        let source = (1..=10)
+            .map(|i| format!("k{} = {}\n", i, i))
+            .collect::<String>();
+        let error = ConfigError::new(Path::new("game.config"), "oops")
+            .at(10, 1)
+            .with_snippet(source.as_bytes());
+        assert_eq!(
+            error.snippet.as_deref(),
+            Some(" 8 | k8 = 8\n 9 | k9 = 9\n10 | k10 = 10\n   | ^")
+        );
+    }
+
+    #[test]
+    fn snippet_at_the_first_line() {
+        let error = ConfigError::new(Path::new("game.config"), "oops")
+            .at(1, 3)
+            .with_snippet(b"a ? 1\nb = 2");
+        assert_eq!(error.snippet.as_deref(), Some("1 | a ? 1\n  |   ^"));
+    }
+
+    #[test]
+    fn no_snippet_without_a_location() {
+        let error = ConfigError::new(Path::new("game.config"), "oops").with_snippet(b"a = 1");
+        assert_eq!(error.snippet, None);
+    }
+}
@@ -25,21 +25,115 @@ use bevy::{
     prelude::*,
 };
 pub use bevy_spicy_data_derive::data_config;
-use serde::de::DeserializeOwned;
-use std::marker::PhantomData;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{fmt, marker::PhantomData, path::Path, time::Duration};
+
+mod changes;
+mod error;
+mod overlay;
+mod readiness;
+mod registry;
+mod resource;
+mod schema;
+mod validation;
+
+pub use changes::ConfigChanged;
+use error::LoadResults;
+pub use error::{ConfigError, ConfigLoadFailed};
+use overlay::ErrorOverlay;
+pub use readiness::{configs_loaded, ConfigNotLoaded, ConfigReadiness, NotLoadedReason};
+use registry::LoadedConfigs;
+use resource::{AddMirror, MirroredConfig};
+pub use schema::{Schema, SchemaDrift, SchemaField, TypeMismatch, UnknownKeys};
+use validation::Validators;
 
 #[doc(hidden)]
 pub mod private {
+    pub use crate::registry::SubAsset;
     pub use ::bevy::app::App;
-    pub use ::bevy::asset::{AddAsset, LoadContext, LoadedAsset};
+    pub use ::bevy::asset::{AddAsset, AssetPath, AssetServer, Handle, LoadContext, LoadedAsset};
     pub use ::bevy::reflect::TypeUuid;
     pub use ::bevy::reflect::Uuid;
+    pub use ::once_cell::sync::Lazy;
+    pub use ::regex::Regex;
     pub use ::serde;
+    pub use ::toml;
+
+    use std::convert::TryFrom;
+
+    /// An integer type that values of a config can be stored as
+    pub trait Integer: TryFrom<i64> + std::fmt::Display {
+        /// The smallest value of this type
+        const MIN: Self;
+        /// The largest value of this type
+        const MAX: Self;
+    }
+
+    macro_rules! impl_integer {
+        ($($ty:ty),*) => {
+            $(impl Integer for $ty {
+                const MIN: Self = <$ty>::MIN;
+                const MAX: Self = <$ty>::MAX;
+            })*
+        };
+    }
+
+    impl_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+    /// Adds the given config type as an asset
+    ///
+    /// Returns `false` if it has already been added, in which case its systems
+    /// do not have to be added again either.
+    pub fn add_asset<T: super::Config>(app: &mut App) -> bool {
+        if app.world.contains_resource::<super::Assets<T>>() {
+            return false;
+        }
+
+        app.add_asset::<T>();
+        true
+    }
+
+    /// Reads a datetime, which `toml::Value` and other formats hand out as a string
+    pub fn datetime<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<toml::value::Datetime, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Datetime {
+            Datetime(toml::value::Datetime),
+            String(String),
+        }
+
+        match <Datetime as serde::Deserialize>::deserialize(deserializer)? {
+            Datetime::Datetime(datetime) => Ok(datetime),
+            Datetime::String(string) => string.parse().map_err(serde::de::Error::custom),
+        }
+    }
+
+    /// Converts an integer read from a file into the type chosen for it
+    pub fn integer_in_range<T: Integer>(value: i64, key_path: &str) -> Result<T, String> {
+        T::try_from(value).map_err(|_| {
+            format!(
+                "`{}` is out of range: {} does not fit into {} ({}..={})",
+                key_path,
+                value,
+                std::any::type_name::<T>(),
+                T::MIN,
+                T::MAX
+            )
+        })
+    }
 }
 
 #[derive(Debug)]
 /// Plugin for a given config struct, use the `Root` if you want to include all of the toml file
 ///
+/// By default, files ending in `.config` are loaded. Use [`TomlConfigPlugin::with_extensions`]
+/// to choose other extensions, for example to bind different configs to different files.
+///
+/// You can add as many of these plugins as you need, they share the [`SyncStage`] and
+/// each config type is only registered once.
+///
 /// ## Examples
 ///
 /// ```rust
@@ -51,36 +145,309 @@ pub mod private {
 ///
 /// ```
 pub struct TomlConfigPlugin<T: Config> {
+    loaders: Vec<(ConfigFormat, Vec<&'static str>)>,
+    overlay_font: Option<&'static str>,
+    validators: Validators<T>,
+    resources: Vec<(&'static str, AddMirror)>,
+    change_events: Vec<fn(&mut App)>,
+    ui_texts: Vec<fn(&mut App)>,
+    required: Vec<&'static str>,
+    load_timeout: Option<Duration>,
     kind: PhantomData<T>,
 }
 
+impl<T: Config> TomlConfigPlugin<T> {
+    /// Creates a plugin loading toml files with the given extensions
+    ///
+    /// Extensions may contain dots, which allows binding a config to a part of the file name:
+    /// with `"balance.config"`, the file `game.balance.config` is loaded as this config,
+    /// while other plugins can still use `"config"` for the remaining `.config` files.
+    /// An extension can only be used by a single plugin.
+    pub fn with_extensions(extensions: &[&'static str]) -> Self {
+        Self::with_format(ConfigFormat::Toml, extensions)
+    }
+
+    /// Creates a plugin loading files in the given format with the given extensions
+    pub fn with_format(format: ConfigFormat, extensions: &[&'static str]) -> Self {
+        Self {
+            loaders: vec![(format, extensions.to_vec())],
+            overlay_font: None,
+            validators: Validators::default(),
+            resources: vec![],
+            change_events: vec![],
+            ui_texts: vec![],
+            required: vec![],
+            load_timeout: None,
+            kind: PhantomData,
+        }
+    }
+
+    /// Additionally loads files in the given format with the given extensions
+    pub fn and_format(mut self, format: ConfigFormat, extensions: &[&'static str]) -> Self {
+        self.loaders.push((format, extensions.to_vec()));
+        self
+    }
+
+    /// Shows the files that fail to load on screen, using the font at the given asset path
+    ///
+    /// Each error is shown with the lines leading up to it, and disappears once the file loads again.
+    /// The overlay is drawn by bevy_ui, so it needs a UI camera. A monospaced font lines up the
+    /// marker under the offending column.
+    pub fn with_error_overlay(mut self, font: &'static str) -> Self {
+        self.overlay_font = Some(font);
+        self
+    }
+
+    /// Checks every file loaded by this plugin before it replaces the loaded config
+    ///
+    /// Files for which the check returns an error are rejected like files that could not be read:
+    /// the previously loaded config stays in place and a [`ConfigLoadFailed`] event is sent.
+    ///
+    /// ```rust,ignore
+    /// TomlConfigPlugin::<config::Root>::default().with_validator(|config| {
+    ///     if config.player.max_health() > 0 {
+    ///         Ok(())
+    ///     } else {
+    ///         Err(String::from("player.max_health needs to be positive"))
+    ///     }
+    /// })
+    /// ```
+    pub fn with_validator(
+        mut self,
+        validator: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Loads the file at `path` when the app starts, and keeps a copy of it as a resource
+    ///
+    /// The resource is inserted once the file has loaded, and replaced whenever it is reloaded,
+    /// so `Res<T>` can be used for change detection. Until then, systems need to take an `Option<Res<T>>`.
+    /// Only a single path can be kept as a resource for each type, later ones are ignored with a warning.
+    ///
+    /// The file is required by [`configs_loaded`], like with [`TomlConfigPlugin::require`].
+    pub fn with_resource(self, path: &'static str) -> Self {
+        self.with_sub_resource::<T>(path)
+    }
+
+    /// Loads the sub-asset at `path` when the app starts, and keeps a copy of it as a resource
+    ///
+    /// Works like [`TomlConfigPlugin::with_resource`], for parts of a config:
+    /// `with_sub_resource::<config::Player>("game.config#player")`.
+    pub fn with_sub_resource<S: Config>(mut self, path: &'static str) -> Self {
+        self.resources.push((path, MirroredConfig::<S>::add));
+        self
+    }
+
+    /// Sends [`ConfigChanged`] events for the config type `S`, which is `T` or any part of it
+    ///
+    /// Each type keeps a copy of its previous values to send along, so the events are only sent
+    /// for the types asked for: `with_change_events::<config::player::MaxHealth>()`.
+    pub fn with_change_events<S: Config>(mut self) -> Self {
+        self.change_events.push(ConfigChanged::<S>::add);
+        self
+    }
+
+    /// Keeps the texts showing a value of type `S` through [`UiDataText`] in sync with it
+    ///
+    /// Each type needs its own system, so texts are only kept in sync for the types asked for:
+    /// `with_ui_text::<config::player::Gold>()`.
+    pub fn with_ui_text<S: Config + UiValue>(mut self) -> Self {
+        self.ui_texts.push(UiDataText::<S>::add);
+        self
+    }
+
+    /// Loads the file at `path` when the app starts, and holds systems using [`configs_loaded`] until it has loaded
+    ///
+    /// A file that is missing or fails to load sends a [`ConfigNotLoaded`] event, and keeps holding those systems
+    /// until it is fixed.
+    pub fn require(mut self, path: &'static str) -> Self {
+        self.required.push(path);
+        self
+    }
+
+    /// Sends a [`ConfigNotLoaded`] event for each required file that has not loaded this long after the app started
+    ///
+    /// The timeout applies to the files required by all plugins.
+    pub fn with_load_timeout(mut self, timeout: Duration) -> Self {
+        self.load_timeout = Some(timeout);
+        self
+    }
+}
+
 impl<T: Config + Sync + Send + 'static> Plugin for TomlConfigPlugin<T> {
     fn build(&self, app: &mut App) {
-        app.add_stage_after(CoreStage::Update, SyncStage, SystemStage::parallel());
+        if app.schedule.get_stage::<SystemStage>(&SyncStage).is_none() {
+            app.add_stage_after(CoreStage::Update, SyncStage, SystemStage::parallel());
+        }
 
         T::add_asset(app);
-        app.add_asset_loader(TomlAssetLoader::<T>::default());
+
+        let results = match app.world.get_resource::<LoadResults<T>>() {
+            Some(results) => results.clone(),
+            None => {
+                let results = LoadResults::<T>::default();
+                app.insert_resource(results.clone())
+                    .add_event::<ConfigLoadFailed<T>>()
+                    .add_system_to_stage(CoreStage::PreUpdate, ConfigLoadFailed::<T>::send_events);
+                results
+            }
+        };
+
+        let loaded = match app.world.get_resource::<LoadedConfigs<T>>() {
+            Some(loaded) => loaded.clone(),
+            None => {
+                let loaded = LoadedConfigs::<T>::default();
+                app.insert_resource(loaded.clone())
+                    .add_system_to_stage(CoreStage::PreUpdate, LoadedConfigs::<T>::release_dropped)
+                    .add_system_to_stage(
+                        CoreStage::PreUpdate,
+                        LoadedConfigs::<T>::remove_stale.exclusive_system(),
+                    );
+                loaded
+            }
+        };
+
+        if let Some(font) = self.overlay_font {
+            if !app.world.contains_resource::<ErrorOverlay>() {
+                app.insert_resource(ErrorOverlay::new(font))
+                    .add_system(ErrorOverlay::show_errors);
+            }
+        }
+
+        let mut required = self.required.clone();
+        for (path, add) in &self.resources {
+            if add(app, path) {
+                required.push(*path);
+            }
+        }
+
+        for add in self.change_events.iter().chain(&self.ui_texts) {
+            add(app);
+        }
+
+        if !required.is_empty() || self.load_timeout.is_some() {
+            if !app.world.contains_resource::<ConfigReadiness>() {
+                app.init_resource::<ConfigReadiness>()
+                    .add_event::<ConfigNotLoaded>()
+                    .add_startup_system(ConfigReadiness::load_required)
+                    .add_system_to_stage(CoreStage::PreUpdate, ConfigReadiness::update);
+            }
+
+            let mut readiness = app
+                .world
+                .get_resource_or_insert_with(ConfigReadiness::default);
+            for path in &required {
+                readiness.require(path);
+            }
+            if let Some(timeout) = self.load_timeout {
+                readiness.set_timeout(timeout);
+            }
+        }
+
+        for (format, extensions) in &self.loaders {
+            app.add_asset_loader(TomlAssetLoader::<T> {
+                results: results.clone(),
+                validators: self.validators.clone(),
+                loaded: loaded.clone(),
+                ..TomlAssetLoader::with_format(*format, extensions)
+            });
+        }
     }
 }
 
 impl<T: Config> Default for TomlConfigPlugin<T> {
     fn default() -> Self {
-        Self {
-            kind: Default::default(),
+        Self::with_extensions(&["config"])
+    }
+}
+
+/// The formats config files can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// [TOML](https://toml.io), the default
+    Toml,
+    /// [JSON](https://www.json.org)
+    Json,
+    /// [RON](https://github.com/ron-rs/ron), tables are written as structs: `(max_health: 200)`
+    Ron,
+}
+
+impl ConfigFormat {
+    /// Parses a document in this format, read from the file at `path`
+    ///
+    /// All formats are read into the same representation, so that the types generated by
+    /// `data_config!` can be deserialized from any of them.
+    pub fn parse(&self, path: &Path, bytes: &[u8]) -> Result<toml::Value, ConfigError> {
+        match self {
+            ConfigFormat::Toml => toml::from_slice(bytes).map_err(|err| {
+                let error = ConfigError::new(path, &err);
+                match err.line_col() {
+                    Some((line, column)) => error.at(line + 1, column + 1),
+                    None => error,
+                }
+            }),
+            ConfigFormat::Json => serde_json::from_slice(bytes).map_err(|err| {
+                let error = ConfigError::new(path, &err);
+                match err.line() {
+                    0 => error,
+                    line => error.at(line, err.column()),
+                }
+            }),
+            // RON only allows deserializing the keys of structs as identifiers
+            ConfigFormat::Ron => match ron::de::from_bytes::<ron::Value>(bytes) {
+                Ok(value) => {
+                    toml::Value::deserialize(value).map_err(|err| ConfigError::new(path, err))
+                }
+                Err(err) => {
+                    Err(ConfigError::new(path, err.code).at(err.position.line, err.position.col))
+                }
+            },
         }
     }
 }
 
 /// The asset loader for the data you wish to load from a given file
 ///
+/// Despite its name, it can read any of the [`ConfigFormat`]s.
 /// You should not need to interact with it directly as the [`TomlConfigPlugin`] will
 /// add it for you correctly.
+///
+/// Files that fail to load are reported through [`ConfigLoadFailed`] events.
 #[derive(Debug)]
-pub struct TomlAssetLoader<T: Config>(PhantomData<T>);
+pub struct TomlAssetLoader<T: Config> {
+    format: ConfigFormat,
+    extensions: Vec<&'static str>,
+    results: LoadResults<T>,
+    validators: Validators<T>,
+    /// The configs loaded last, to only replace the sub-assets that changed
+    loaded: LoadedConfigs<T>,
+    kind: PhantomData<T>,
+}
+
+impl<T: Config> TomlAssetLoader<T> {
+    /// Creates a loader for toml files with the given extensions
+    pub fn new(extensions: &[&'static str]) -> Self {
+        Self::with_format(ConfigFormat::Toml, extensions)
+    }
+
+    /// Creates a loader for files in the given format with the given extensions
+    pub fn with_format(format: ConfigFormat, extensions: &[&'static str]) -> Self {
+        Self {
+            format,
+            extensions: extensions.to_vec(),
+            results: LoadResults::default(),
+            validators: Validators::default(),
+            loaded: Default::default(),
+            kind: PhantomData,
+        }
+    }
+}
 
 impl<T: Config> Default for TomlAssetLoader<T> {
     fn default() -> Self {
-        Self(Default::default())
+        Self::new(&["config"])
     }
 }
 
@@ -91,96 +458,416 @@ impl<T: Config + Sync + Send + 'static> AssetLoader for TomlAssetLoader<T> {
         load_context: &'a mut bevy::asset::LoadContext,
     ) -> bevy::asset::BoxedFuture<'a, Result<(), anyhow::Error>> {
         Box::pin(async move {
-            let config: T = toml::from_slice(bytes)?;
+            let path = load_context.path();
+            let config = self.format.parse(path, bytes).and_then(|document| {
+                let drift = T::schema().diff(&document);
+
+                let config = match T::deserialize(document) {
+                    Ok(config) => config,
+                    Err(_) if drift.explains_failure(T::lenient(), T::unknown_keys()) => {
+                        return Err(ConfigError::new(path, &drift).with_drift(drift))
+                    }
+                    // Drift that the config tolerates is only kept around for context
+                    Err(err) if drift.is_empty() => return Err(ConfigError::new(path, err)),
+                    Err(err) => return Err(ConfigError::new(path, err).with_drift(drift)),
+                };
+
+                let mut failures = config.validate(vec![]);
+                failures.extend(self.validators.validate(&config));
 
-            config.register(load_context, None);
+                if failures.is_empty() {
+                    Ok((config, drift))
+                } else {
+                    Err(ConfigError::new(
+                        path,
+                        format!("validation failed: {}", failures.join("; ")),
+                    ))
+                }
+            });
 
-            Ok(())
+            match config {
+                Ok((config, drift)) => {
+                    // Only configs generated with `lenient` load with missing keys
+                    if !drift.missing.is_empty() {
+                        warn!(
+                            "{} is missing {}, using the values it was compiled with",
+                            path.display(),
+                            drift.missing.join(", ")
+                        );
+                    }
+                    if !drift.extra.is_empty() && T::unknown_keys() == UnknownKeys::Warn {
+                        warn!(
+                            "{} has unknown keys {}",
+                            path.display(),
+                            drift.extra.join(", ")
+                        );
+                    }
+
+                    self.results.push(Ok(path.to_owned()));
+
+                    let previous = self.loaded.insert(path, config.clone());
+                    let mut sub_assets = vec![];
+                    config.register(load_context, None, previous.as_ref(), &mut sub_assets);
+                    self.loaded.hold(load_context.path(), sub_assets);
+
+                    Ok(())
+                }
+                Err(error) => {
+                    let error = error.with_snippet(bytes);
+                    // Returning the error keeps the previously loaded assets in place
+                    self.results.push(Err(error.clone()));
+
+                    Err(error.into())
+                }
+            }
         })
     }
 
     fn extensions(&self) -> &[&str] {
-        &["config"]
+        &self.extensions
     }
 }
 
 /// The principal trait for a piece of configuration
-pub trait Config: DeserializeOwned + Asset {
+pub trait Config: DeserializeOwned + Asset + Clone + PartialEq {
     /// Register a piece of data at the given path.
     ///
     /// This allows you to only reference to a specific
     /// part of your configuration using bevy's subassets.
+    ///
+    /// When reloading, `previous` is the value loaded before. Sub-assets that did not change
+    /// are not set again, so no `AssetEvent::Modified` is sent for them.
+    ///
+    /// All sub-assets are pushed to `sub_assets`, whether they were set again or not. The loader
+    /// keeps them until they disappear from the file, or the root of the file is dropped.
     fn register<'a>(
         &self,
         load_context: &'a mut bevy::asset::LoadContext,
         path: Option<Vec<String>>,
+        previous: Option<&Self>,
+        sub_assets: &mut Vec<private::SubAsset>,
     );
 
     /// Register the given config piece as an asset
     fn add_asset(app: &mut bevy::app::App);
+
+    /// Checks the constraints given to `data_config!`, returning a message for each value violating them
+    ///
+    /// `path` is the key path of this piece of the config, used to name the values in the messages.
+    fn validate(&self, path: Vec<String>) -> Vec<String>;
+
+    /// The structure of the file this config was generated from
+    ///
+    /// Files read at runtime are compared against it, to report how they differ.
+    fn schema() -> Schema;
+
+    /// What happens to keys of a file read at runtime which are not part of this config
+    fn unknown_keys() -> UnknownKeys;
+
+    /// Whether keys missing from a file read at runtime keep the values this config was compiled with
+    fn lenient() -> bool;
 }
 
 #[derive(StageLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// The stage after which all components are updated
 pub struct SyncStage;
 
+/// A single value of a config that can be shown by [`UiDataText`]
+///
+/// Implemented by `data_config!` for every string, number, boolean and datetime.
+pub trait UiValue: fmt::Display {
+    /// Whether this value is a number, which the precision and thousands separator of [`UiDataText`] apply to
+    const NUMERIC: bool;
+}
+
 /// Allows you to keep a single UiText in sync with your data
-pub struct UiDataText<T: Config + AsRef<str>>(pub Handle<T>);
+///
+/// Any single value of a config can be shown: strings, numbers, booleans and datetimes. Its type needs to be
+/// given to [`TomlConfigPlugin::with_ui_text`].
+/// By default the value replaces the whole first section of the text. A different section can be
+/// targeted with [`UiDataText::with_section`], and [`UiDataText::with_template`] keeps some text around the value:
+///
+/// ```rust,ignore
+/// commands
+///     .spawn_bundle(TextBundle { text: Text { sections: vec![label, health], ..Default::default() }, ..Default::default() })
+///     .insert(UiDataText::new(health_handle).with_section(1).with_template("Health: {}"));
+/// ```
+pub struct UiDataText<T: Config + UiValue> {
+    /// The value to show
+    pub handle: Handle<T>,
+    /// The index of the section of the text that shows the value
+    pub section: usize,
+    /// The text of the section, with each `{}` or `{placeholder}` replaced by the value
+    pub template: Option<String>,
+    /// The number of digits shown after the decimal point of floats
+    pub precision: Option<usize>,
+    /// The character put between each group of three digits of integers and floats
+    pub thousands_separator: Option<char>,
+}
 
-impl<T: Config + AsRef<str>> UiDataText<T> {
-    #[doc(hidden)]
-    pub fn keep_in_sync(
-        mut text_asset_event: EventReader<AssetEvent<T>>,
-        text_assets: Res<Assets<T>>,
-        mut text_query: Query<(&UiDataText<T>, &mut Text)>,
-    ) {
-        for event in text_asset_event.iter() {
-            match event {
-                AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
-                    let config_text = if let Some(conf) = text_assets.get(handle) {
-                        conf.as_ref()
-                    } else {
-                        continue;
-                    };
-
-                    for (ui_data_text, mut text) in text_query.iter_mut() {
-                        if &ui_data_text.0 == handle {
-                            if let Some(text) = text.sections.first_mut() {
-                                text.value = config_text.to_owned();
-                            } else {
-                                error!("A text component with UiDataText does not have any sections to update");
-                                continue;
-                            }
-                        }
-                    }
-                }
-                AssetEvent::Removed { .. } => (),
-            }
+impl<T: Config + UiValue> UiDataText<T> {
+    /// Shows the given value in the first section of the text
+    pub fn new(handle: Handle<T>) -> Self {
+        Self {
+            handle,
+            section: 0,
+            template: None,
+            precision: None,
+            thousands_separator: None,
+        }
+    }
+
+    /// Shows the value in the section with the given index instead, leaving the other sections untouched
+    pub fn with_section(mut self, section: usize) -> Self {
+        self.section = section;
+        self
+    }
+
+    /// Fills the placeholders of the given template with the value
+    ///
+    /// Placeholders are written as `{}`, or with a name like `{health}` to be easier to read.
+    /// Every placeholder is replaced with the same value, `{{` and `}}` are kept as single braces,
+    /// and a `{` that is never closed is shown as it is.
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// Shows floats with the given number of digits after the decimal point, `1.5` becomes `1.50` with a precision of 2
+    ///
+    /// Other values are not affected.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// Separates the digits of numbers into groups of three, `1234567` becomes `1,234,567` with `','`
+    ///
+    /// Only integers and floats are grouped, strings made of digits are shown as they are.
+    pub fn with_thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    fn format(&self, value: &T) -> String {
+        if !T::NUMERIC {
+            return value.to_string();
+        }
+
+        let formatted = match self.precision {
+            Some(precision) => format!("{:.*}", precision, value),
+            None => value.to_string(),
+        };
+
+        match self.thousands_separator {
+            Some(separator) => group_thousands(&formatted, separator),
+            None => formatted,
+        }
+    }
+
+    fn render(&self, value: &T) -> String {
+        let value = self.format(value);
+        match &self.template {
+            Some(template) => fill_template(template, &value),
+            None => value,
+        }
+    }
+
+    fn update(&self, text: &mut Text, value: &T) {
+        match text.sections.get_mut(self.section) {
+            Some(section) => section.value = self.render(value),
+            None => error!(
+                "A text component with UiDataText does not have a section {} to update, it has {}",
+                self.section,
+                text.sections.len()
+            ),
         }
     }
 
-    #[doc(hidden)]
-    pub fn when_inserted(
+    /// Adds the system keeping the texts in sync, unless another plugin already did
+    fn add(app: &mut App) {
+        if app.world.contains_resource::<SyncedUiTexts<T>>() {
+            return;
+        }
+
+        app.insert_resource(SyncedUiTexts::<T>(PhantomData))
+            .add_system_to_stage(SyncStage, Self::keep_in_sync);
+    }
+
+    fn keep_in_sync(
+        mut text_asset_event: EventReader<AssetEvent<T>>,
         text_assets: Res<Assets<T>>,
-        mut text_query: Query<
-            (&UiDataText<T>, &mut Text),
-            Or<(Added<UiDataText<T>>, Changed<UiDataText<T>>)>,
-        >,
+        inserted_query: Query<Entity, Changed<UiDataText<T>>>,
+        mut text_query: Query<(Entity, &UiDataText<T>, &mut Text)>,
     ) {
-        for (ui_data_text, mut text) in text_query.iter_mut() {
-            let config_text = if let Some(conf) = text_assets.get(&ui_data_text.0) {
-                conf.as_ref()
-            } else {
-                debug!("Could not find text for: {} ({:?})", std::any::type_name::<T>(), ui_data_text.0);
+        let changed: Vec<&Handle<T>> = text_asset_event
+            .iter()
+            .filter_map(|event| match event {
+                AssetEvent::Created { handle } | AssetEvent::Modified { handle } => Some(handle),
+                AssetEvent::Removed { .. } => None,
+            })
+            .collect();
+        let inserted: Vec<Entity> = inserted_query.iter().collect();
+
+        for (entity, ui_data_text, mut text) in text_query.iter_mut() {
+            // Texts that were just inserted or changed are filled in, whether their value changed or not
+            if !inserted.contains(&entity) && !changed.contains(&&ui_data_text.handle) {
                 continue;
-            };
+            }
 
-            if let Some(text) = text.sections.first_mut() {
-                text.value = config_text.to_owned();
+            let value = if let Some(value) = text_assets.get(&ui_data_text.handle) {
+                value
             } else {
-                error!("A text component with UiDataText does not have any sections to update");
+                debug!(
+                    "Could not find text for: {} ({:?})",
+                    std::any::type_name::<T>(),
+                    ui_data_text.handle
+                );
                 continue;
+            };
+
+            ui_data_text.update(&mut text, value);
+        }
+    }
+}
+
+/// Marks the value types whose [`UiDataText`]s are kept in sync
+struct SyncedUiTexts<T>(PhantomData<T>);
+
+/// Separates the digits of the integer part of a formatted number into groups of three
+///
+/// Anything that does not look like a number, like `NaN`, is returned as it is.
+fn group_thousands(number: &str, separator: char) -> String {
+    let sign = if number.starts_with('-') { 1 } else { 0 };
+    let digits = number[sign..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(number.len(), |end| sign + end);
+    if digits == sign || !(number[digits..].is_empty() || number[digits..].starts_with('.')) {
+        return number.to_owned();
+    }
+
+    let mut grouped = String::with_capacity(number.len() + digits / 3);
+    grouped.push_str(&number[..sign]);
+    for (index, digit) in number[sign..digits].chars().enumerate() {
+        if index > 0 && (digits - sign - index) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped.push_str(&number[digits..]);
+    grouped
+}
+
+/// Replaces each `{}` or `{placeholder}` of the template with the value
+///
+/// `{{` and `}}` are kept as single braces, a `{` that is never closed is kept as it is.
+fn fill_template(template: &str, value: &str) -> String {
+    let mut rendered = String::with_capacity(template.len() + value.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
             }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::from('{');
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    placeholder.push(c);
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                }
+
+                if closed {
+                    rendered.push_str(value);
+                } else {
+                    rendered.push_str(&placeholder);
+                }
+            }
+            c => rendered.push(c),
         }
     }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(format: ConfigFormat, source: &str) -> Option<(usize, usize)> {
+        format
+            .parse(Path::new("game.config"), source.as_bytes())
+            .err()
+            .and_then(|error| error.location)
+    }
+
+    #[test]
+    fn locations_start_at_one_in_every_format() {
+        assert_eq!(location(ConfigFormat::Toml, "a = 1\nb = ?"), Some((2, 5)));
+        assert_eq!(
+            location(ConfigFormat::Json, "{\"a\": 1,\n\"b\": ?}"),
+            Some((2, 6))
+        );
+        assert_eq!(location(ConfigFormat::Ron, "(a: 1,\nb: ?)"), Some((2, 4)));
+    }
+
+    #[test]
+    fn formats_parse_to_the_same_value() {
+        let toml = ConfigFormat::Toml.parse(Path::new("a"), b"a = 1\n[b]\nc = \"d\"");
+        let json = ConfigFormat::Json.parse(Path::new("a"), br#"{"a": 1, "b": {"c": "d"}}"#);
+        let ron = ConfigFormat::Ron.parse(Path::new("a"), br#"(a: 1, b: (c: "d"))"#);
+        assert_eq!(toml, json);
+        assert_eq!(toml, ron);
+    }
+
+    #[test]
+    fn template_placeholders_are_filled() {
+        assert_eq!(fill_template("Health: {}", "200"), "Health: 200");
+        assert_eq!(fill_template("{health} / {max}", "200"), "200 / 200");
+        assert_eq!(fill_template("no placeholder", "200"), "no placeholder");
+        assert_eq!(fill_template("", "200"), "");
+    }
+
+    #[test]
+    fn template_braces_can_be_escaped() {
+        assert_eq!(fill_template("{{}} {{{}}}", "200"), "{} {200}");
+        assert_eq!(fill_template("a } b", "200"), "a } b");
+    }
+
+    #[test]
+    fn unclosed_template_placeholders_are_kept() {
+        assert_eq!(fill_template("Health: {hp", "200"), "Health: {hp");
+        assert_eq!(fill_template("{} {", "200"), "200 {");
+    }
+
+    #[test]
+    fn thousands_are_grouped() {
+        assert_eq!(group_thousands("0", ','), "0");
+        assert_eq!(group_thousands("123", ','), "123");
+        assert_eq!(group_thousands("1000", ','), "1,000");
+        assert_eq!(group_thousands("1234567", '_'), "1_234_567");
+    }
+
+    #[test]
+    fn thousands_keep_the_sign_and_fraction() {
+        assert_eq!(group_thousands("-123", ','), "-123");
+        assert_eq!(group_thousands("-123456", ','), "-123,456");
+        assert_eq!(group_thousands("-12345.6789", ','), "-12,345.6789");
+        assert_eq!(group_thousands("1000.0", '.'), "1.000.0");
+    }
+
+    #[test]
+    fn thousands_leave_non_numbers_alone() {
+        assert_eq!(group_thousands("NaN", ','), "NaN");
+        assert_eq!(group_thousands("-inf", ','), "-inf");
+        assert_eq!(group_thousands("-", ','), "-");
+        assert_eq!(group_thousands("", ','), "");
+    }
 }
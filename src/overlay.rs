@@ -0,0 +1,95 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+
+use crate::ConfigError;
+
+/// Keeps track of the config files that currently fail to load, to show them on screen
+#[derive(Debug)]
+pub(crate) struct ErrorOverlay {
+    font: &'static str,
+    errors: BTreeMap<PathBuf, ConfigError>,
+}
+
+/// Marks the text showing the [`ErrorOverlay`]
+pub(crate) struct ErrorOverlayText;
+
+impl ErrorOverlay {
+    pub(crate) fn new(font: &'static str) -> Self {
+        Self {
+            font,
+            errors: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn failed(&mut self, error: ConfigError) {
+        self.errors.insert(error.path.clone(), error);
+    }
+
+    pub(crate) fn loaded(&mut self, path: &Path) {
+        self.errors.remove(path);
+    }
+
+    pub(crate) fn show_errors(
+        mut commands: Commands,
+        overlay: Res<ErrorOverlay>,
+        asset_server: Res<AssetServer>,
+        texts: Query<Entity, With<ErrorOverlayText>>,
+    ) {
+        if !overlay.is_changed() {
+            return;
+        }
+
+        for entity in texts.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        if overlay.errors.is_empty() {
+            return;
+        }
+
+        let font = asset_server.load(overlay.font);
+        let style = |font_size, color| TextStyle {
+            font: font.clone(),
+            font_size,
+            color,
+        };
+
+        let mut sections = vec![];
+        for error in overlay.errors.values() {
+            sections.push(TextSection {
+                value: format!("{}\n", error),
+                style: style(24.0, Color::RED),
+            });
+
+            if let Some(snippet) = &error.snippet {
+                sections.push(TextSection {
+                    value: format!("{}\n", snippet),
+                    style: style(20.0, Color::WHITE),
+                });
+            }
+        }
+
+        commands
+            .spawn_bundle(TextBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        top: Val::Px(10.0),
+                        left: Val::Px(10.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text {
+                    sections,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(ErrorOverlayText);
+    }
+}
@@ -0,0 +1,147 @@
+use std::{collections::HashSet, time::Duration};
+
+use bevy::{asset::LoadState, ecs::schedule::ShouldRun, prelude::*};
+
+/// Why a config required by [`configs_loaded`] is not available
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotLoadedReason {
+    /// The file is missing, could not be parsed, or was rejected by a validator
+    Failed,
+    /// The file did not finish loading within the timeout given to
+    /// [`TomlConfigPlugin::with_load_timeout`](crate::TomlConfigPlugin::with_load_timeout)
+    TimedOut,
+}
+
+/// Sent for each config required by [`configs_loaded`] that could not be loaded
+///
+/// Each file is reported once, [`ConfigLoadFailed`](crate::ConfigLoadFailed) holds the details of what went wrong.
+/// Systems stay on hold until the file is fixed and reloaded.
+#[derive(Debug, Clone)]
+pub struct ConfigNotLoaded {
+    /// The asset path of the config
+    pub path: &'static str,
+    /// Why it is not available
+    pub reason: NotLoadedReason,
+}
+
+/// Tracks whether the configs required by the plugins have been loaded
+///
+/// Use [`configs_loaded`] to hold systems until that is the case.
+#[derive(Debug, Default)]
+pub struct ConfigReadiness {
+    required: Vec<&'static str>,
+    handles: Vec<(&'static str, HandleUntyped)>,
+    timeout: Option<Duration>,
+    reported: HashSet<&'static str>,
+    timed_out: bool,
+    ready: bool,
+}
+
+impl ConfigReadiness {
+    /// Whether every required config has been loaded
+    ///
+    /// Once this is the case, it stays so: later reloads that fail keep the previous values in place.
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// The asset paths of the required configs that have not been loaded yet
+    pub fn pending<'a>(
+        &'a self,
+        asset_server: &'a AssetServer,
+    ) -> impl Iterator<Item = &'static str> + 'a {
+        self.handles
+            .iter()
+            .filter(move |(_, handle)| asset_server.get_load_state(handle) != LoadState::Loaded)
+            .map(|(path, _)| *path)
+    }
+
+    pub(crate) fn require(&mut self, path: &'static str) {
+        if !self.required.contains(&path) {
+            self.required.push(path);
+        }
+    }
+
+    pub(crate) fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    pub(crate) fn load_required(
+        asset_server: Res<AssetServer>,
+        mut readiness: ResMut<ConfigReadiness>,
+    ) {
+        let readiness = &mut *readiness;
+        readiness.handles = readiness
+            .required
+            .iter()
+            .map(|path| (*path, asset_server.load_untyped(*path)))
+            .collect();
+    }
+
+    pub(crate) fn update(
+        asset_server: Res<AssetServer>,
+        time: Res<Time>,
+        mut readiness: ResMut<ConfigReadiness>,
+        mut events: EventWriter<ConfigNotLoaded>,
+    ) {
+        if readiness.ready {
+            return;
+        }
+
+        let readiness = &mut *readiness;
+        let mut ready = true;
+        for (path, handle) in &readiness.handles {
+            match asset_server.get_load_state(handle) {
+                LoadState::Loaded => {
+                    readiness.reported.remove(path);
+                }
+                LoadState::Failed => {
+                    ready = false;
+                    if readiness.reported.insert(path) {
+                        events.send(ConfigNotLoaded {
+                            path,
+                            reason: NotLoadedReason::Failed,
+                        });
+                    }
+                }
+                _ => ready = false,
+            }
+        }
+
+        if ready {
+            readiness.ready = true;
+            return;
+        }
+
+        let timed_out = matches!(
+            readiness.timeout,
+            Some(timeout) if time.seconds_since_startup() >= timeout.as_secs_f64()
+        );
+        if timed_out && !readiness.timed_out {
+            readiness.timed_out = true;
+            for path in readiness.pending(&asset_server).collect::<Vec<_>>() {
+                if !readiness.reported.contains(path) {
+                    events.send(ConfigNotLoaded {
+                        path,
+                        reason: NotLoadedReason::TimedOut,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Run criteria holding systems until every required config has been loaded
+///
+/// Configs are required through [`TomlConfigPlugin::require`](crate::TomlConfigPlugin::require)
+/// and [`TomlConfigPlugin::with_resource`](crate::TomlConfigPlugin::with_resource).
+///
+/// ```rust,ignore
+/// app.add_system(spawn_enemies.with_run_criteria(configs_loaded));
+/// ```
+pub fn configs_loaded(readiness: Option<Res<ConfigReadiness>>) -> ShouldRun {
+    match readiness {
+        Some(readiness) if !readiness.is_ready() => ShouldRun::No,
+        _ => ShouldRun::Yes,
+    }
+}
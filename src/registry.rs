@@ -0,0 +1,260 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use bevy::{
+    asset::{Asset, AssetPath, HandleId},
+    prelude::*,
+};
+
+use crate::Config;
+
+/// A sub-asset of a config file, as held by its loader
+///
+/// Bevy only frees the sub-assets that were set by the latest load of a file, so those that
+/// disappear from it are removed through `remove` instead.
+pub struct SubAsset {
+    handle: HandleUntyped,
+    remove: fn(&mut World, HandleId),
+}
+
+impl SubAsset {
+    /// Holds the sub-asset behind `handle`
+    pub fn new<S: Asset>(handle: Handle<S>) -> Self {
+        Self {
+            handle: handle.clone_untyped(),
+            remove: remove_asset::<S>,
+        }
+    }
+}
+
+fn remove_asset<S: Asset>(world: &mut World, id: HandleId) {
+    if let Some(mut assets) = world.get_resource_mut::<Assets<S>>() {
+        assets.remove(id);
+    }
+}
+
+/// A config file as it was loaded last
+struct LoadedConfig<T> {
+    config: T,
+    /// The sub-assets of the file, which live as long as they are part of it and its root is loaded
+    sub_assets: Vec<SubAsset>,
+}
+
+/// The configs of type `T` that are currently loaded, shared between its loaders and the app
+///
+/// Each file holds on to the sub-assets it still contains, until its root is dropped. Then they are removed
+/// and the file is forgotten, so that loading it again sets all of its sub-assets anew.
+pub(crate) struct LoadedConfigs<T> {
+    loaded: Arc<Mutex<HashMap<PathBuf, LoadedConfig<T>>>>,
+    /// Sub-assets that disappeared from their file or whose root was dropped, waiting to be removed
+    stale: Arc<Mutex<Vec<SubAsset>>>,
+}
+
+impl<T> LoadedConfigs<T> {
+    /// Stores the config loaded from `path`, returning the one loaded before
+    pub(crate) fn insert(&self, path: &Path, config: T) -> Option<T> {
+        let mut loaded = self.loaded.lock().ok()?;
+        match loaded.get_mut(path) {
+            Some(entry) => Some(std::mem::replace(&mut entry.config, config)),
+            None => {
+                loaded.insert(
+                    path.to_owned(),
+                    LoadedConfig {
+                        config,
+                        sub_assets: vec![],
+                    },
+                );
+                None
+            }
+        }
+    }
+
+    /// Keeps the given sub-assets of `path` alive, queueing those that are no longer part of it for removal
+    pub(crate) fn hold(&self, path: &Path, sub_assets: Vec<SubAsset>) {
+        let current: HashSet<HandleId> = sub_assets
+            .iter()
+            .map(|sub_asset| sub_asset.handle.id)
+            .collect();
+        let previous = match self.loaded.lock() {
+            Ok(mut loaded) => match loaded.get_mut(path) {
+                Some(entry) => std::mem::replace(&mut entry.sub_assets, sub_assets),
+                None => return,
+            },
+            Err(_) => return,
+        };
+
+        if let Ok(mut stale) = self.stale.lock() {
+            stale.extend(
+                previous
+                    .into_iter()
+                    .filter(|sub_asset| !current.contains(&sub_asset.handle.id)),
+            );
+        }
+    }
+
+    /// Forgets the file whose root has the given id, queueing all of its sub-assets for removal
+    ///
+    /// Bevy cannot free the sub-assets that were left unchanged by the latest load, so they are removed
+    /// like those that disappeared from the file.
+    fn forget(&self, root: HandleId) {
+        let mut loaded = match self.loaded.lock() {
+            Ok(loaded) => loaded,
+            Err(_) => return,
+        };
+        let dropped: Vec<PathBuf> = loaded
+            .keys()
+            .filter(|path| HandleId::from(AssetPath::new_ref(path, None)) == root)
+            .cloned()
+            .collect();
+
+        if let Ok(mut stale) = self.stale.lock() {
+            for path in dropped {
+                if let Some(entry) = loaded.remove(&path) {
+                    stale.extend(entry.sub_assets);
+                }
+            }
+        }
+    }
+}
+
+impl<T: Config> LoadedConfigs<T> {
+    /// Forgets the files whose root has been dropped, releasing their sub-assets
+    pub(crate) fn release_dropped(
+        configs: Res<LoadedConfigs<T>>,
+        mut events: EventReader<AssetEvent<T>>,
+    ) {
+        for event in events.iter() {
+            if let AssetEvent::Removed { handle } = event {
+                configs.forget(handle.id);
+            }
+        }
+    }
+
+    /// Removes the sub-assets that disappeared from their file, or whose root has been dropped
+    pub(crate) fn remove_stale(world: &mut World) {
+        let stale = match world.get_resource::<LoadedConfigs<T>>() {
+            Some(configs) => match configs.stale.lock() {
+                Ok(mut stale) => std::mem::take(&mut *stale),
+                Err(_) => return,
+            },
+            None => return,
+        };
+
+        for SubAsset { handle, remove } in stale {
+            remove(world, handle.id);
+        }
+    }
+}
+
+impl<T> Clone for LoadedConfigs<T> {
+    fn clone(&self) -> Self {
+        Self {
+            loaded: Arc::clone(&self.loaded),
+            stale: Arc::clone(&self.stale),
+        }
+    }
+}
+
+impl<T> Default for LoadedConfigs<T> {
+    fn default() -> Self {
+        Self {
+            loaded: Default::default(),
+            stale: Default::default(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for LoadedConfigs<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoadedConfigs").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::reflect::TypeUuid;
+
+    use super::*;
+
+    #[derive(TypeUuid)]
+    #[uuid = "5e0ee1a4-4d0c-4c8e-9b6e-2f1e3a0c7d11"]
+    struct Value;
+
+    fn root(path: &str) -> HandleId {
+        HandleId::from(AssetPath::new_ref(Path::new(path), None))
+    }
+
+    fn sub_asset(path: &str, label: &str) -> SubAsset {
+        let id = HandleId::from(AssetPath::new_ref(Path::new(path), Some(label)));
+        SubAsset::new(Handle::<Value>::weak(id))
+    }
+
+    fn stale(configs: &LoadedConfigs<u32>) -> Vec<HandleId> {
+        let stale = configs.stale.lock().expect("the lock is not poisoned");
+        stale.iter().map(|sub_asset| sub_asset.handle.id).collect()
+    }
+
+    #[test]
+    fn insert_returns_the_previous_config() {
+        let configs = LoadedConfigs::<u32>::default();
+
+        assert_eq!(configs.insert(Path::new("a.config"), 1), None);
+        assert_eq!(configs.insert(Path::new("b.config"), 2), None);
+        assert_eq!(configs.insert(Path::new("a.config"), 3), Some(1));
+    }
+
+    #[test]
+    fn hold_queues_the_sub_assets_that_disappeared() {
+        let configs = LoadedConfigs::<u32>::default();
+        let path = Path::new("a.config");
+        configs.insert(path, 1);
+
+        configs.hold(
+            path,
+            vec![
+                sub_asset("a.config", "waves.0"),
+                sub_asset("a.config", "waves.1"),
+            ],
+        );
+        assert!(stale(&configs).is_empty());
+
+        configs.insert(path, 2);
+        configs.hold(path, vec![sub_asset("a.config", "waves.0")]);
+        assert_eq!(
+            stale(&configs),
+            vec![sub_asset("a.config", "waves.1").handle.id]
+        );
+    }
+
+    #[test]
+    fn hold_ignores_files_that_are_not_loaded() {
+        let configs = LoadedConfigs::<u32>::default();
+
+        configs.hold(Path::new("a.config"), vec![sub_asset("a.config", "title")]);
+        configs.hold(Path::new("a.config"), vec![]);
+        assert!(stale(&configs).is_empty());
+    }
+
+    #[test]
+    fn forget_queues_every_sub_asset_of_the_dropped_root() {
+        let configs = LoadedConfigs::<u32>::default();
+        configs.insert(Path::new("a.config"), 1);
+        configs.hold(Path::new("a.config"), vec![sub_asset("a.config", "title")]);
+        configs.insert(Path::new("b.config"), 2);
+        configs.hold(Path::new("b.config"), vec![sub_asset("b.config", "title")]);
+
+        configs.forget(root("a.config"));
+        assert_eq!(
+            stale(&configs),
+            vec![sub_asset("a.config", "title").handle.id]
+        );
+
+        // Loading it again starts over
+        assert_eq!(configs.insert(Path::new("a.config"), 3), None);
+        assert_eq!(configs.insert(Path::new("b.config"), 4), Some(2));
+    }
+}
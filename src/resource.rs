@@ -0,0 +1,84 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::Config;
+
+/// Adds the systems mirroring the config at the given path into a resource, returning `false` if it is ignored
+pub(crate) type AddMirror = fn(&mut App, &'static str) -> bool;
+
+/// The handle of a config that is kept in sync with a resource of the same type
+pub(crate) struct MirroredConfig<T: Config> {
+    handle: Handle<T>,
+}
+
+/// The path a config type is mirrored from, as only a single one can be kept as a resource
+struct MirroredPath<T: Config> {
+    path: &'static str,
+    kind: PhantomData<T>,
+}
+
+impl<T: Config> MirroredConfig<T> {
+    /// Loads the config at the given path when the app starts, and keeps a copy of it as a resource
+    ///
+    /// If the type is already kept as a resource, the path is ignored with a warning.
+    pub(crate) fn add(app: &mut App, path: &'static str) -> bool {
+        if let Some(mirrored) = app.world.get_resource::<MirroredPath<T>>() {
+            if mirrored.path != path {
+                warn!(
+                    "{} is already kept as a resource from {}, not also from {}",
+                    std::any::type_name::<T>(),
+                    mirrored.path,
+                    path
+                );
+            }
+            return false;
+        }
+
+        app.insert_resource(MirroredPath::<T> {
+            path,
+            kind: PhantomData,
+        })
+        .add_startup_system(
+            move |mut commands: Commands, asset_server: Res<AssetServer>| {
+                commands.insert_resource(MirroredConfig::<T> {
+                    handle: asset_server.load(path),
+                });
+            },
+        )
+        .add_system_to_stage(CoreStage::PreUpdate, Self::keep_in_sync);
+        true
+    }
+
+    fn keep_in_sync(
+        mut commands: Commands,
+        mut events: EventReader<AssetEvent<T>>,
+        assets: Res<Assets<T>>,
+        mirrored: Option<Res<MirroredConfig<T>>>,
+    ) {
+        let mirrored = match mirrored {
+            Some(mirrored) => mirrored,
+            None => return,
+        };
+
+        // The config may have already been loaded through another handle
+        if mirrored.is_added() {
+            if let Some(config) = assets.get(&mirrored.handle) {
+                commands.insert_resource(config.clone());
+            }
+        }
+
+        for event in events.iter() {
+            match event {
+                AssetEvent::Created { handle } | AssetEvent::Modified { handle }
+                    if *handle == mirrored.handle =>
+                {
+                    if let Some(config) = assets.get(handle) {
+                        commands.insert_resource(config.clone());
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
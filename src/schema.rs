@@ -0,0 +1,340 @@
+use std::fmt;
+
+/// The structure of a config, as found by `data_config!` at compile time
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    /// A string
+    String,
+    /// An integer
+    Integer,
+    /// A float, integers are accepted as well
+    Float,
+    /// A boolean
+    Boolean,
+    /// A TOML datetime, strings are accepted as well
+    Datetime,
+    /// An array whose values all have the given structure
+    Array(Box<Schema>),
+    /// A table with the given keys
+    Table(Vec<SchemaField>),
+}
+
+/// A key of a [`Schema::Table`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaField {
+    /// The name of the key
+    pub name: &'static str,
+    /// The structure of its value
+    pub schema: Schema,
+    /// Whether the key may be missing
+    pub optional: bool,
+}
+
+impl SchemaField {
+    /// Creates a field with the given name
+    pub fn new(name: &'static str, schema: Schema, optional: bool) -> Self {
+        Self {
+            name,
+            schema,
+            optional,
+        }
+    }
+}
+
+impl Schema {
+    /// The name of this kind of value, as used by [`toml::Value::type_str`]
+    pub fn type_str(&self) -> &'static str {
+        match self {
+            Schema::String => "string",
+            Schema::Integer => "integer",
+            Schema::Float => "float",
+            Schema::Boolean => "boolean",
+            Schema::Datetime => "datetime",
+            Schema::Array(_) => "array",
+            Schema::Table(_) => "table",
+        }
+    }
+
+    /// Compares a document read at runtime against this schema
+    pub fn diff(&self, document: &toml::Value) -> SchemaDrift {
+        let mut drift = SchemaDrift::default();
+        self.diff_value(&mut vec![], document, &mut drift);
+        drift
+    }
+
+    fn diff_value(&self, path: &mut Vec<String>, value: &toml::Value, drift: &mut SchemaDrift) {
+        match (self, value) {
+            (Schema::Table(fields), toml::Value::Table(table)) => {
+                for field in fields {
+                    path.push(field.name.to_owned());
+                    match table.get(field.name) {
+                        Some(value) => field.schema.diff_value(path, value, drift),
+                        None if field.optional => (),
+                        None => drift.missing.push(path.join(".")),
+                    }
+                    path.pop();
+                }
+
+                for key in table.keys() {
+                    if !fields.iter().any(|field| field.name == key) {
+                        path.push(key.clone());
+                        drift.extra.push(path.join("."));
+                        path.pop();
+                    }
+                }
+            }
+            (Schema::Array(schema), toml::Value::Array(values)) => {
+                for (index, value) in values.iter().enumerate() {
+                    path.push(index.to_string());
+                    schema.diff_value(path, value, drift);
+                    path.pop();
+                }
+            }
+            (Schema::Float, toml::Value::Integer(_))
+            | (Schema::Datetime, toml::Value::String(_)) => (),
+            (schema, value) if schema.type_str() == value.type_str() => (),
+            (schema, value) => drift.mismatched.push(TypeMismatch {
+                path: path.join("."),
+                expected: schema.type_str(),
+                found: value.type_str(),
+            }),
+        }
+    }
+}
+
+/// What happens to keys of a file read at runtime which are not part of its config
+///
+/// Chosen with the `strict` option of `data_config!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownKeys {
+    /// Unknown keys are ignored, the default
+    Ignore,
+    /// Unknown keys are logged as a warning, with `strict = warn`
+    Warn,
+    /// Files with unknown keys fail to load, with `strict`
+    Deny,
+}
+
+/// The differences between a document read at runtime and the [`Schema`] of its config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaDrift {
+    /// The paths of the keys that are missing from the document
+    pub missing: Vec<String>,
+    /// The paths of the keys that are not part of the schema
+    pub extra: Vec<String>,
+    /// The values that have a different type than in the schema
+    pub mismatched: Vec<TypeMismatch>,
+}
+
+/// A value that has a different type than in the [`Schema`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeMismatch {
+    /// The path of the value, empty for the document itself
+    pub path: String,
+    /// The kind of value the schema contains
+    pub expected: &'static str,
+    /// The kind of value that was found
+    pub found: &'static str,
+}
+
+impl SchemaDrift {
+    /// Whether the document matches the schema
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.mismatched.is_empty()
+    }
+
+    /// Whether these differences are enough for the document to fail to deserialize
+    ///
+    /// Missing keys are filled in by `lenient` configs, and unknown keys only matter with `strict`.
+    pub(crate) fn explains_failure(&self, lenient: bool, unknown_keys: UnknownKeys) -> bool {
+        !self.mismatched.is_empty()
+            || (!lenient && !self.missing.is_empty())
+            || (unknown_keys == UnknownKeys::Deny && !self.extra.is_empty())
+    }
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![];
+        if !self.missing.is_empty() {
+            lines.push(format!("missing keys: {}", self.missing.join(", ")));
+        }
+        if !self.extra.is_empty() {
+            lines.push(format!("unknown keys: {}", self.extra.join(", ")));
+        }
+        for mismatch in &self.mismatched {
+            let path = if mismatch.path.is_empty() {
+                "the document"
+            } else {
+                &mismatch.path
+            };
+            lines.push(format!(
+                "{}: expected {}, found {}",
+                path, mismatch.expected, mismatch.found
+            ));
+        }
+        // A missing key next to an unknown one is more likely a typo than a new structure
+        let mut changed = !self.mismatched.is_empty();
+        for missing in &self.missing {
+            match self
+                .extra
+                .iter()
+                .find(|extra| parent_path(extra) == parent_path(missing))
+            {
+                Some(extra) => {
+                    lines.push(format!("hint: `{}` may be a typo of `{}`", extra, missing))
+                }
+                None => changed = true,
+            }
+        }
+        if changed {
+            lines.push(String::from(
+                "hint: the structure of the file changed since it was compiled, \
+                 rebuild to update the generated types",
+            ));
+        }
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// The path of the table containing the given key
+fn parent_path(key: &str) -> &str {
+    key.rsplit_once('.').map_or("", |(parent, _)| parent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        Schema::Table(vec![
+            SchemaField::new("title", Schema::String, false),
+            SchemaField::new("when", Schema::Datetime, false),
+            SchemaField::new(
+                "player",
+                Schema::Table(vec![
+                    SchemaField::new("max_health", Schema::Integer, false),
+                    SchemaField::new("speed", Schema::Float, false),
+                    SchemaField::new(
+                        "spawn_points",
+                        Schema::Array(Box::new(Schema::Integer)),
+                        false,
+                    ),
+                ]),
+                false,
+            ),
+            SchemaField::new(
+                "waves",
+                Schema::Array(Box::new(Schema::Table(vec![
+                    SchemaField::new("count", Schema::Integer, false),
+                    SchemaField::new("boss", Schema::String, true),
+                ]))),
+                false,
+            ),
+        ])
+    }
+
+    fn diff(document: &str) -> SchemaDrift {
+        schema().diff(&toml::from_str(document).expect("valid toml"))
+    }
+
+    const MATCHING: &str = r#"
+        title = "x"
+        when = 1979-05-27T07:32:00Z
+        [player]
+        max_health = 200
+        speed = 1.5
+        spawn_points = [1, 2]
+        [[waves]]
+        count = 3
+        boss = "big"
+        [[waves]]
+        count = 5
+    "#;
+
+    #[test]
+    fn matching_document_has_no_drift() {
+        assert!(diff(MATCHING).is_empty());
+    }
+
+    #[test]
+    fn integers_are_floats_and_strings_are_datetimes() {
+        let drift = diff(&MATCHING.replace("speed = 1.5", "speed = 1").replace(
+            "when = 1979-05-27T07:32:00Z",
+            r#"when = "1979-05-27T07:32:00Z""#,
+        ));
+        assert!(drift.is_empty(), "{:?}", drift);
+    }
+
+    #[test]
+    fn optional_keys_may_be_missing() {
+        let drift = diff(&MATCHING.replace(r#"boss = "big""#, ""));
+        assert!(drift.is_empty(), "{:?}", drift);
+    }
+
+    #[test]
+    fn reports_missing_extra_and_mismatched_keys_by_path() {
+        let drift = diff(
+            &MATCHING
+                .replace("max_health = 200", "max_helth = 200")
+                .replace("spawn_points = [1, 2]", r#"spawn_points = [1, "2"]"#)
+                .replace("count = 5", "count = 5.5"),
+        );
+
+        assert_eq!(drift.missing, vec!["player.max_health"]);
+        assert_eq!(drift.extra, vec!["player.max_helth"]);
+        assert_eq!(
+            drift.mismatched,
+            vec![
+                TypeMismatch {
+                    path: String::from("player.spawn_points.1"),
+                    expected: "integer",
+                    found: "string",
+                },
+                TypeMismatch {
+                    path: String::from("waves.1.count"),
+                    expected: "integer",
+                    found: "float",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn mismatched_document_is_named() {
+        let drift = Schema::Table(vec![]).diff(&toml::Value::Integer(1));
+        assert_eq!(
+            drift.to_string().lines().next(),
+            Some("the document: expected table, found integer")
+        );
+    }
+
+    #[test]
+    fn drift_explains_failure_only_when_the_config_does_not_tolerate_it() {
+        let extra = diff(&MATCHING.replace("[player]", "foo = 1\n[player]"));
+        assert!(!extra.explains_failure(false, UnknownKeys::Ignore));
+        assert!(!extra.explains_failure(false, UnknownKeys::Warn));
+        assert!(extra.explains_failure(false, UnknownKeys::Deny));
+
+        let missing = diff(&MATCHING.replace(r#"title = "x""#, ""));
+        assert!(missing.explains_failure(false, UnknownKeys::Ignore));
+        assert!(!missing.explains_failure(true, UnknownKeys::Ignore));
+
+        let mismatched = diff(&MATCHING.replace(r#"title = "x""#, "title = 1"));
+        assert!(mismatched.explains_failure(true, UnknownKeys::Ignore));
+    }
+
+    #[test]
+    fn typos_are_not_reported_as_a_changed_structure() {
+        let typo = diff(&MATCHING.replace("max_health", "max_helth")).to_string();
+        assert!(typo.contains("hint: `player.max_helth` may be a typo of `player.max_health`"));
+        assert!(!typo.contains("rebuild"));
+
+        let unknown = diff(&MATCHING.replace("[player]", "foo = 1\n[player]")).to_string();
+        assert_eq!(unknown, "unknown keys: foo");
+
+        let missing = diff(&MATCHING.replace(r#"title = "x""#, "")).to_string();
+        assert!(missing.contains("rebuild"));
+    }
+}
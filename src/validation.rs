@@ -0,0 +1,47 @@
+use std::{fmt, sync::Arc};
+
+type Validator<T> = dyn Fn(&T) -> Result<(), String> + Send + Sync;
+
+/// The checks a config has to pass before it replaces the loaded one
+pub(crate) struct Validators<T> {
+    validators: Vec<Arc<Validator<T>>>,
+}
+
+impl<T> Validators<T> {
+    pub(crate) fn push(
+        &mut self,
+        validator: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.validators.push(Arc::new(validator));
+    }
+
+    /// Runs all checks, returning the messages of those that failed
+    pub(crate) fn validate(&self, config: &T) -> Vec<String> {
+        self.validators
+            .iter()
+            .filter_map(|validator| validator(config).err())
+            .collect()
+    }
+}
+
+impl<T> Clone for Validators<T> {
+    fn clone(&self) -> Self {
+        Self {
+            validators: self.validators.clone(),
+        }
+    }
+}
+
+impl<T> Default for Validators<T> {
+    fn default() -> Self {
+        Self { validators: vec![] }
+    }
+}
+
+impl<T> fmt::Debug for Validators<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Validators")
+            .field("count", &self.validators.len())
+            .finish()
+    }
+}
@@ -0,0 +1,142 @@
+use bevy_spicy_data::{data_config, Config, UiValue, UnknownKeys};
+
+data_config!(pub config, "tests/data_config.toml", types {
+    "player.max_health" => u16,
+    "player.jump_force" => f32,
+}, constraints {
+    "player.max_health" => (min = 1, max = 500),
+    "player.name" => (regex = "^[A-Za-z]+$", max_len = 10),
+    "waves.*.count" => (min = 1),
+});
+
+data_config!(pub lenient_config, "tests/data_config.toml", lenient);
+
+data_config!(pub strict_config, "tests/data_config.toml", strict);
+
+data_config!(pub empty_config, "tests/empty_array.toml", types {
+    "inventory" => String,
+});
+
+const SOURCE: &str = include_str!("data_config.toml");
+
+#[test]
+fn fields_have_the_chosen_types() {
+    let config: config::Root = toml::from_str(SOURCE).expect("the source loads");
+
+    assert_eq!(config.title(), "Spicy");
+    assert_eq!(config.load(), 0.5);
+    assert_eq!(config.server().load(), 0.5);
+
+    let max_health: u16 = config.player().max_health();
+    let jump_force: f32 = config.player().jump_force();
+    assert_eq!(max_health, 200);
+    assert_eq!(jump_force, 1.0);
+    assert!(config.player().alive());
+    assert_eq!(config.player().spawn_points(), &[1, 2, 3]);
+}
+
+#[test]
+fn arrays_of_tables_merge_their_entries() {
+    let config: config::Root = toml::from_str(SOURCE).expect("the source loads");
+    let waves = config.waves();
+
+    assert_eq!(waves.len(), 2);
+    assert_eq!(waves[0].count(), 3);
+    assert_eq!(waves[0].boss(), Some("big"));
+    assert_eq!(waves[0].label(), "first");
+    assert_eq!(waves[1].count(), 5);
+    assert_eq!(waves[1].boss(), None);
+}
+
+#[test]
+fn types_know_their_labels() {
+    assert_eq!(config::player::MaxHealth::LABEL, "player.max_health");
+    assert_eq!(config::server::Load::LABEL, "server.load");
+    assert_eq!(config::waves::Entry::LABEL, "waves.*");
+    assert_eq!(config::waves::Entry::label_at([1]), "waves.1");
+    assert_eq!(config::waves::entry::Label::label_at([0]), "waves.0.label");
+}
+
+#[test]
+fn empty_arrays_take_the_chosen_type() {
+    let config: empty_config::Root =
+        toml::from_str(include_str!("empty_array.toml")).expect("the source loads");
+    assert!(config.inventory().is_empty());
+
+    let config: empty_config::Root =
+        toml::from_str(r#"inventory = ["sword", "shield"]"#).expect("strings can be added");
+    let inventory: &[String] = config.inventory();
+    assert_eq!(inventory, ["sword", "shield"]);
+    assert!(toml::from_str::<empty_config::Root>("inventory = [1]").is_err());
+}
+
+#[test]
+fn constraints_are_checked() {
+    let config: config::Root = toml::from_str(SOURCE).expect("the source loads");
+    assert_eq!(config.validate(vec![]), Vec::<String>::new());
+
+    let source = SOURCE
+        .replace("max_health = 200", "max_health = 600")
+        .replace(r#"name = "Ferris""#, r#"name = "Ferris the crab""#)
+        .replace("count = 5", "count = 0");
+    let config: config::Root = toml::from_str(&source).expect("the source loads");
+    let failures = config.validate(vec![]);
+
+    assert_eq!(failures.len(), 4, "{:?}", failures);
+    for path in ["player.max_health", "player.name", "waves.1.count"] {
+        assert!(
+            failures.iter().any(|failure| failure.contains(path)),
+            "no failure for {} in {:?}",
+            path,
+            failures
+        );
+    }
+}
+
+#[test]
+fn lenient_configs_fill_in_missing_keys() {
+    let source = SOURCE
+        .replace("max_health = 200", "")
+        .replace("[server]\nload = 0.5", "");
+
+    assert!(toml::from_str::<config::Root>(&source).is_err());
+    let config: lenient_config::Root = toml::from_str(&source).expect("missing keys are filled in");
+    assert_eq!(config.player().max_health(), 200);
+    assert_eq!(config.server().load(), 0.5);
+    assert!(lenient_config::Root::lenient());
+    assert!(!config::Root::lenient());
+}
+
+#[test]
+fn strict_configs_reject_unknown_keys() {
+    let source = SOURCE.replace("max_health = 200", "max_health = 200\nmax_helth = 200");
+
+    assert!(toml::from_str::<config::Root>(&source).is_ok());
+    assert!(toml::from_str::<strict_config::Root>(&source).is_err());
+    assert_eq!(strict_config::Root::unknown_keys(), UnknownKeys::Deny);
+    assert_eq!(config::Root::unknown_keys(), UnknownKeys::Ignore);
+}
+
+#[test]
+fn schema_matches_the_source() {
+    let document: toml::Value = toml::from_str(SOURCE).expect("the source parses");
+
+    assert!(config::Root::schema().diff(&document).is_empty());
+}
+
+fn is_numeric<T: UiValue>() -> bool {
+    T::NUMERIC
+}
+
+#[test]
+fn only_numbers_are_numeric_ui_values() {
+    assert!(is_numeric::<config::player::MaxHealth>());
+    assert!(is_numeric::<config::player::JumpForce>());
+    assert!(!is_numeric::<config::player::Alive>());
+    assert!(!is_numeric::<config::player::Name>());
+    assert!(!is_numeric::<config::When>());
+
+    let config: config::Root = toml::from_str(SOURCE).expect("the source loads");
+    assert_eq!(format!("{:.2}", config.player.jump_force), "1.00");
+    assert_eq!(config.player.alive.to_string(), "true");
+}